@@ -0,0 +1,88 @@
+//! # Automatic backups of the workspace manifest
+
+use crate::errors::{Result, univer_error};
+use crate::utils;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+
+/// Name of the directory with backups in the univer working directory.
+const BACKUPS_DIR_NAME: &str = "backups";
+
+/// Maximum number of kept backups, the oldest ones are removed.
+const MAX_BACKUPS: usize = 20;
+
+/// Backup of the workspace manifest.
+#[derive(Debug, Clone)]
+pub struct Backup {
+  /// Path to the backup file.
+  pub path: Utf8PathBuf,
+  /// Time of creating the backup, in milliseconds since Unix epoch.
+  pub timestamp: u128,
+  /// Name of the command that created the backup.
+  pub command: String,
+}
+
+impl Backup {
+  /// Returns the time of creating the backup in `YYYY-MM-DD HH:MM:SS` format (UTC).
+  pub fn time(&self) -> String {
+    utils::format_timestamp((self.timestamp / 1_000) as u64)
+  }
+}
+
+/// Creates the backup of the workspace manifest before it is modified by the command.
+pub fn create(univer_dir: &Utf8Path, manifest_path: &Utf8Path, command: &str) -> Result<Backup> {
+  let backups_dir = univer_dir.join(BACKUPS_DIR_NAME);
+  std::fs::create_dir_all(&backups_dir).map_err(|e| univer_error!("failed to create directory {}, reason: {}", backups_dir, e))?;
+  let mut timestamp = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or_default();
+  // Backups created within the same millisecond get subsequent timestamps.
+  while backups_dir.join(file_name(timestamp, command)).exists() {
+    timestamp += 1;
+  }
+  let path = backups_dir.join(file_name(timestamp, command));
+  utils::write_file(&path, utils::read_file(manifest_path)?)?;
+  // Remove the oldest backups.
+  for backup in list(univer_dir)?.iter().skip(MAX_BACKUPS) {
+    std::fs::remove_file(&backup.path).map_err(|e| univer_error!("failed to remove backup {}, reason: {}", backup.path, e))?;
+  }
+  Ok(Backup {
+    path,
+    timestamp,
+    command: command.to_string(),
+  })
+}
+
+/// Returns backups of the workspace manifest, the newest first.
+pub fn list(univer_dir: &Utf8Path) -> Result<Vec<Backup>> {
+  let backups_dir = univer_dir.join(BACKUPS_DIR_NAME);
+  let mut backups = vec![];
+  if !backups_dir.exists() {
+    return Ok(backups);
+  }
+  let entries = backups_dir
+    .read_dir_utf8()
+    .map_err(|e| univer_error!("failed to read directory {}, reason: {}", backups_dir, e))?;
+  for entry in entries {
+    let entry = entry.map_err(|e| univer_error!("failed to read directory {}, reason: {}", backups_dir, e))?;
+    // Files not matching the backup file name format are ignored.
+    let Some((timestamp, command)) = entry.file_name().strip_suffix(".toml").and_then(|stem| stem.split_once('-')) else {
+      continue;
+    };
+    let Ok(timestamp) = timestamp.parse::<u128>() else {
+      continue;
+    };
+    backups.push(Backup {
+      path: entry.path().to_path_buf(),
+      timestamp,
+      command: command.to_string(),
+    });
+  }
+  backups.sort_by_key(|backup| std::cmp::Reverse(backup.timestamp));
+  Ok(backups)
+}
+
+/// Returns the name of the backup file.
+fn file_name(timestamp: u128, command: &str) -> String {
+  format!("{}-{}.toml", timestamp, command)
+}
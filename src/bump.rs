@@ -0,0 +1,231 @@
+//! # Bumping the workspace version through pre-release channels
+
+use crate::config::Options;
+use crate::errors::{Result, univer_error};
+use crate::model::Workspace;
+use crate::output::{auto, info};
+use crate::{backup, lock, output, utils};
+use antex::StyledText;
+use cargo_metadata::semver::{BuildMetadata, Prerelease, Version};
+use std::path::Path;
+
+/// Part of the version incremented when a new pre-release cycle starts.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Level {
+  /// Major version, like `3.0.2` -> `4.0.0-rc.1`.
+  Major,
+  /// Minor version, like `3.0.2` -> `3.1.0-rc.1`.
+  Minor,
+  /// Patch version, like `3.0.2` -> `3.0.3-rc.1`.
+  #[default]
+  Patch,
+}
+
+impl Level {
+  /// Creates the level from its name.
+  pub fn new(name: &str) -> Option<Self> {
+    match name {
+      "major" => Some(Level::Major),
+      "minor" => Some(Level::Minor),
+      "patch" => Some(Level::Patch),
+      _ => None,
+    }
+  }
+}
+
+/// Change of the workspace version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bump {
+  /// Next pre-release in the channel, like `rc`, starting a new cycle at the level when the version is not a pre-release.
+  Pre(String, Level),
+  /// Final release, the pre-release is stripped.
+  Release,
+}
+
+/// Bumps the version defined in `[workspace.package]` table.
+///
+/// Crates declaring their own versions (independent versions mode) are not changed.
+pub fn bump(manifest_dir: &Path, bump: &Bump, dry_run: bool, accept_all: bool, options: &Options) -> Result<()> {
+  let workspace = Workspace::load(manifest_dir, options)?;
+  let config = workspace.config();
+  if output::is_verbose() {
+    config.report();
+  }
+  if config.locked {
+    lock::verify(&workspace)?;
+  }
+  let current_version = workspace.version();
+  let next_version = next_version(current_version, bump)?;
+  info!();
+  info!(
+    "Bump version: {} -> {}",
+    auto().bold().s(current_version).reset(),
+    auto().bold().green().s(&next_version).reset()
+  );
+  info!();
+  if dry_run || !utils::prompt("Do you want to bump the version?", accept_all)? {
+    return Ok(());
+  }
+  let mut manifest_content = replace_workspace_version(&utils::read_file(workspace.manifest_path())?, &next_version)?;
+  // In hybrid mode versions of dependencies follow versions of crates.
+  if config.hybrid {
+    for member in workspace.members.iter().filter(|member| member.version == current_version) {
+      let mut member = member.clone();
+      member.version = next_version.clone();
+      manifest_content = member.sync_dependency(&manifest_content, config.req_style(&member.name))?;
+    }
+  }
+  backup::create(&workspace.univer_dir(), workspace.manifest_path(), "bump")?;
+  utils::write_file(workspace.manifest_path(), manifest_content)?;
+  // Update the lock file to reflect changed versions of workspace crates.
+  if config.update_lock {
+    lock::report(&lock::update(&workspace)?);
+  }
+  Ok(())
+}
+
+/// Returns the version following the specified one.
+///
+/// The pre-release counter is incremented within the same channel, switching the channel starts
+/// counting from `1`. The next version must be greater than the current one, so channels
+/// are expected to follow each other alphabetically, like `alpha`, `beta` and `rc`.
+pub fn next_version(version: &str, bump: &Bump) -> Result<String> {
+  let current = Version::parse(version).map_err(|e| univer_error!("invalid version '{}', reason: {}", version, e))?;
+  let mut next = current.clone();
+  next.build = BuildMetadata::EMPTY;
+  match bump {
+    Bump::Pre(channel, level) => {
+      if channel.is_empty() || channel.chars().all(|ch| ch.is_ascii_digit()) || !channel.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-') {
+        return Err(univer_error!("invalid pre-release channel '{}'", channel));
+      }
+      let counter = current
+        .pre
+        .as_str()
+        .strip_prefix(channel.as_str())
+        .and_then(|rest| rest.strip_prefix('.'))
+        .and_then(|counter| counter.parse::<u64>().ok());
+      if current.pre.is_empty() {
+        match level {
+          Level::Major => (next.major, next.minor, next.patch) = (next.major + 1, 0, 0),
+          Level::Minor => (next.minor, next.patch) = (next.minor + 1, 0),
+          Level::Patch => next.patch += 1,
+        }
+      }
+      let pre = format!("{}.{}", channel, counter.map(|counter| counter + 1).unwrap_or(1));
+      next.pre = Prerelease::new(&pre).map_err(|e| univer_error!("invalid pre-release '{}', reason: {}", pre, e))?;
+      if next <= current {
+        return Err(univer_error!("version '{}' is not greater than '{}'", next, current));
+      }
+    }
+    Bump::Release => {
+      if current.pre.is_empty() {
+        return Err(univer_error!("version '{}' is not a pre-release", current));
+      }
+      next.pre = Prerelease::EMPTY;
+    }
+  }
+  Ok(next.to_string())
+}
+
+/// Returns `true` when the version is a pre-release, like `3.1.0-rc.1`.
+pub fn is_pre_release(version: &str) -> bool {
+  Version::parse(version).is_ok_and(|version| !version.pre.is_empty())
+}
+
+/// Replaces the version in `[workspace.package]` table, the rest of the manifest is preserved verbatim.
+///
+/// Supported is the `version = "..."` (or `version = '...'`) line in `[workspace.package]` table,
+/// other layouts, like dotted keys or inline tables, are reported as errors instead of being left unchanged.
+fn replace_workspace_version(content: &str, version: &str) -> Result<String> {
+  let workspace_version = |content: &str| -> Result<Option<String>> {
+    let manifest: toml::Table = toml::from_str(content).map_err(|e| univer_error!("failed to parse workspace manifest, reason: {}", e))?;
+    Ok(
+      manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("package"))
+        .and_then(|package| package.get("version"))
+        .and_then(toml::Value::as_str)
+        .map(str::to_string),
+    )
+  };
+  if workspace_version(content)?.is_none() {
+    return Err(univer_error!("missing 'version' in [workspace.package] table"));
+  }
+  let unsupported = || univer_error!("unsupported layout of 'version' in [workspace.package] table, expected 'version = \"...\"' line");
+  let mut section = "";
+  let mut offset = 0;
+  for line in content.split_inclusive('\n') {
+    let trimmed = line.trim();
+    if trimmed.starts_with('[') {
+      section = trimmed.trim_start_matches('[').split(']').next().unwrap_or_default().trim();
+    } else if section == "workspace.package"
+      && let Some((key, value)) = trimmed.split_once('=')
+      && key.trim().trim_matches(['"', '\'']) == "version"
+    {
+      let value = value.trim_start();
+      let Some(quote) = value.chars().next().filter(|quote| ['"', '\''].contains(quote)) else {
+        return Err(unsupported());
+      };
+      if value.starts_with(&quote.to_string().repeat(3)) {
+        return Err(unsupported());
+      }
+      let start = offset + line.len() - line.trim_start().len() + trimmed.len() - value.len();
+      let Some(length) = content[start + 1..].find(quote) else {
+        return Err(unsupported());
+      };
+      let replaced = format!("{}{quote}{}{quote}{}", &content[..start], version, &content[start + 1 + length + 1..]);
+      // Make sure the version was replaced in the right place.
+      if workspace_version(&replaced)?.as_deref() != Some(version) {
+        return Err(unsupported());
+      }
+      return Ok(replaced);
+    }
+    offset += line.len();
+  }
+  Err(unsupported())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_replace_double_quoted_version() {
+    let content = "[workspace.package]\nversion = \"0.1.0\" # current\nedition = \"2021\"\n";
+    assert_eq!(
+      "[workspace.package]\nversion = \"0.2.0\" # current\nedition = \"2021\"\n",
+      replace_workspace_version(content, "0.2.0").unwrap()
+    );
+  }
+
+  #[test]
+  fn test_replace_single_quoted_version() {
+    let content = "[workspace]\nmembers = ['packages/*']\n\n[workspace.package]\n'version'='0.1.0'\n";
+    assert_eq!(
+      "[workspace]\nmembers = ['packages/*']\n\n[workspace.package]\n'version'='0.2.0'\n",
+      replace_workspace_version(content, "0.2.0").unwrap()
+    );
+  }
+
+  #[test]
+  fn test_unsupported_version_layouts() {
+    let expected = "unsupported layout of 'version' in [workspace.package] table, expected 'version = \"...\"' line";
+    for content in [
+      "[workspace]\npackage.version = \"0.1.0\"\n",
+      "[workspace]\npackage = { version = \"0.1.0\" }\n",
+      "workspace.package.version = \"0.1.0\"\n",
+      "[workspace.package]\nversion = \"\"\"0.1.0\"\"\"\n",
+    ] {
+      assert_eq!(expected, replace_workspace_version(content, "0.2.0").unwrap_err().to_string(), "{}", content);
+    }
+  }
+
+  #[test]
+  fn test_missing_version() {
+    let content = "[workspace.package]\nedition = \"2021\"\n";
+    assert_eq!(
+      "missing 'version' in [workspace.package] table",
+      replace_workspace_version(content, "0.2.0").unwrap_err().to_string()
+    );
+  }
+}
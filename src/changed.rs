@@ -0,0 +1,226 @@
+//! # Detection of crates changed since the previous release
+
+use crate::config::Options;
+use crate::errors::{Result, univer_error};
+use crate::model::{DependencyKind, Member, Workspace};
+use crate::output::auto;
+use crate::utils;
+use antex::StyledText;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Output;
+
+/// Reason why the crate needs a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+  /// Files of the crate changed since the release tag.
+  Changed(String),
+  /// No release tag was found for the crate.
+  NotReleased,
+  /// The crate depends on a changed crate.
+  Dependency(String),
+}
+
+impl std::fmt::Display for Change {
+  /// Implementation of [Display](std::fmt::Display) trait for [Change].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Change::Changed(tag) => write!(f, "changed since {}", tag),
+      Change::NotReleased => write!(f, "not released yet"),
+      Change::Dependency(name) => write!(f, "depends on changed '{}'", name),
+    }
+  }
+}
+
+/// Lists crates that need a release.
+pub fn changed(manifest_dir: &Path, since: Option<&str>, options: &Options) -> Result<()> {
+  let workspace = Workspace::load(manifest_dir, options)?;
+  let changes = detect(&workspace, since)?;
+  let members = utils::sort(workspace.members.clone(), workspace.config().sort)?;
+  println!();
+  if changes.is_empty() {
+    println!("No changed crates");
+    return Ok(());
+  }
+  println!("Changed crates:");
+  for member in members.iter().filter(|member| changes.contains_key(&member.name)) {
+    println!("{}  {}  {}", auto().bold().blue().s(&member.name).reset(), member.path, changes[&member.name]);
+  }
+  if members.iter().any(|member| !changes.contains_key(&member.name)) {
+    println!();
+    println!("Unchanged crates:");
+    for member in members.iter().filter(|member| !changes.contains_key(&member.name)) {
+      println!("{}  {}", auto().bold().blue().s(&member.name).reset(), member.path);
+    }
+  }
+  Ok(())
+}
+
+/// Returns crates that need a release, with the reason.
+///
+/// Files of each crate and the workspace manifest entries it inherits are compared with the previous
+/// release tag (or the specified git reference), tags are found using the tag template, with `{{version}}`
+/// matching any version. Untracked files of the crate are changes too.
+/// Changes are propagated to dependent crates, except through development dependencies.
+pub fn detect(workspace: &Workspace, since: Option<&str>) -> Result<BTreeMap<String, Change>> {
+  let config = workspace.config();
+  let root = workspace.root();
+  let output = git(root, &["rev-parse", "--show-toplevel"])?;
+  if !output.status.success() {
+    return Err(univer_error!("git repository not found in {}", root));
+  }
+  let mut changes = BTreeMap::new();
+  // Crates are visited in the publishing order, so dependencies are visited before dependents.
+  for member in utils::sort(workspace.members.clone(), config.sort)? {
+    let tag = match since {
+      Some(since) => Some(since.to_string()),
+      None => release_tag(workspace, &member)?,
+    };
+    let change = match tag {
+      None => Some(Change::NotReleased),
+      Some(tag) => {
+        if is_changed(workspace, &member, &tag)? {
+          Some(Change::Changed(tag))
+        } else {
+          None
+        }
+      }
+    };
+    let change = change.or_else(|| {
+      member
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.kind != DependencyKind::Development)
+        .find(|dependency| changes.contains_key(&dependency.name))
+        .map(|dependency| Change::Dependency(dependency.name.clone()))
+    });
+    if let Some(change) = change {
+      changes.insert(member.name.clone(), change);
+    }
+  }
+  Ok(changes)
+}
+
+/// Returns `true` when files of the crate or the parts of the workspace manifest used by the crate
+/// differ from the git reference, or the crate has untracked files.
+fn is_changed(workspace: &Workspace, member: &Member, tag: &str) -> Result<bool> {
+  let failed = |output: &Output| {
+    univer_error!(
+      "comparing crate '{}' with '{}' failed, reason: {}",
+      member.name,
+      tag,
+      String::from_utf8_lossy(&output.stderr).trim()
+    )
+  };
+  let output = git(workspace.root(), &["diff", "--quiet", tag, "--", member.manifest_dir.as_str()])?;
+  match output.status.code() {
+    Some(0) => {}
+    Some(1) => return Ok(true),
+    _ => return Err(failed(&output)),
+  }
+  let output = git(workspace.root(), &["status", "--porcelain", "--untracked-files=all", "--", member.manifest_dir.as_str()])?;
+  if !output.status.success() {
+    return Err(failed(&output));
+  }
+  if String::from_utf8_lossy(&output.stdout).lines().any(|line| line.starts_with("??")) {
+    return Ok(true);
+  }
+  is_inherited_changed(workspace, member, tag)
+}
+
+/// Returns `true` when the workspace manifest entries inherited by the crate differ from the git reference.
+///
+/// Compared are the `[workspace.package]` keys inherited by the crate, except the version,
+/// and the `[workspace.dependencies]` entries the crate refers to. For dependencies on workspace
+/// members only the attributes other than `path` and `version` are compared, so switching
+/// between development and publishing mode, or bumping the version, does not change any crate.
+fn is_inherited_changed(workspace: &Workspace, member: &Member, tag: &str) -> Result<bool> {
+  let file_name = workspace.manifest_path().file_name().unwrap_or("Cargo.toml");
+  let output = git(workspace.root(), &["show", &format!("{}:./{}", tag, file_name)])?;
+  if !output.status.success() {
+    // The workspace manifest did not exist at the git reference.
+    return Ok(true);
+  }
+  let previous: toml::Table =
+    toml::from_str(&String::from_utf8_lossy(&output.stdout)).map_err(|e| univer_error!("failed to parse workspace manifest at '{}', reason: {}", tag, e))?;
+  let current = utils::parse_toml(workspace.manifest_path())?;
+  let current = current.as_table().cloned().unwrap_or_default();
+  let manifest = utils::parse_toml(&member.manifest_path)?;
+  let inherited = |table: &toml::Table, section: &str, key: &str| {
+    table
+      .get("workspace")
+      .and_then(|workspace| workspace.get(section))
+      .and_then(|section| section.get(key))
+      .cloned()
+  };
+  if let Some(package) = manifest.get("package").and_then(toml::Value::as_table) {
+    for (key, value) in package {
+      if key != "version" && is_workspace_inherited(value) && inherited(&previous, "package", key) != inherited(&current, "package", key) {
+        return Ok(true);
+      }
+    }
+  }
+  let is_member = |name: &str| workspace.members.iter().any(|member| member.name == name);
+  for dependencies in dependency_tables(&manifest) {
+    for (name, value) in dependencies {
+      if !is_workspace_inherited(value) {
+        continue;
+      }
+      let (mut previous, mut current) = (inherited(&previous, "dependencies", name), inherited(&current, "dependencies", name));
+      if is_member(name) {
+        previous = previous.map(without_location);
+        current = current.map(without_location);
+      }
+      if previous != current {
+        return Ok(true);
+      }
+    }
+  }
+  Ok(false)
+}
+
+/// Returns `true` when the manifest value is inherited from the workspace (`key = { workspace = true }`).
+fn is_workspace_inherited(value: &toml::Value) -> bool {
+  value.get("workspace").and_then(toml::Value::as_bool).unwrap_or(false)
+}
+
+/// Returns all dependency tables of the crate manifest, including target specific ones.
+fn dependency_tables(manifest: &toml::Value) -> Vec<&toml::Table> {
+  const SECTIONS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+  let targets = manifest.get("target").and_then(toml::Value::as_table).into_iter().flat_map(|targets| targets.values());
+  std::iter::once(manifest)
+    .chain(targets)
+    .flat_map(|table| SECTIONS.iter().filter_map(|section| table.get(*section).and_then(toml::Value::as_table)))
+    .collect()
+}
+
+/// Removes `path` and `version` attributes from the workspace dependency entry.
+fn without_location(value: toml::Value) -> toml::Value {
+  match value {
+    toml::Value::Table(mut table) => {
+      table.remove("path");
+      table.remove("version");
+      toml::Value::Table(table)
+    }
+    _ => toml::Value::Table(toml::Table::new()),
+  }
+}
+
+/// Returns the most recent release tag of the crate reachable from `HEAD`.
+pub fn release_tag(workspace: &Workspace, member: &Member) -> Result<Option<String>> {
+  let pattern = workspace.config().tag_name(&member.name, "*");
+  let output = git(workspace.root(), &["describe", "--tags", "--abbrev=0", "--match", &pattern, "HEAD"])?;
+  if !output.status.success() {
+    return Ok(None);
+  }
+  Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|tag| !tag.is_empty()))
+}
+
+/// Executes the git command in the specified directory and returns its output.
+pub fn git(dir: impl AsRef<Path>, args: &[&str]) -> Result<Output> {
+  std::process::Command::new("git")
+    .args(args)
+    .current_dir(dir)
+    .output()
+    .map_err(|e| univer_error!("executing git failed, reason: {}", e))
+}
@@ -1,6 +1,11 @@
+use crate::bump::{Bump, Level};
+use crate::config::{Integration, Options, ReqStyle, SortOrder, parse_req_styles};
 use crate::errors::*;
-use crate::{develop, publish};
-use antex::{StyledText, Text, auto};
+use crate::graph::GraphFormat;
+use crate::output::{ColorChoice, Verbosity, auto_stderr};
+use crate::owners::OwnersCommand;
+use crate::{bump, changed, develop, graph, output, owners, publish, restore, yank};
+use antex::{StyledText, Text};
 use clap::{Arg, ArgAction, ArgMatches, Command, command};
 use std::path::Path;
 
@@ -13,8 +18,8 @@ enum Action {
     bool,
     /// All questions will be answered with `yes` when `true`.
     bool,
-    /// Fixed versions.
-    bool,
+    /// Options overriding the workspace configuration.
+    Options,
   ),
   /// Switch workspace crates to local development mode.
   Develop(
@@ -22,8 +27,82 @@ enum Action {
     String,
     /// All questions will be answered with `yes` when `true`.
     bool,
-    /// Fixed versions.
+    /// Options overriding the workspace configuration.
+    Options,
+  ),
+  /// Export the dependency graph of workspace crates.
+  Graph(
+    /// Path to the manifest file of the workspace.
+    String,
+    /// Output format.
+    GraphFormat,
+    /// Highlight the publishing order when `true`.
+    bool,
+    /// Include crates that are not published when `true`.
+    bool,
+    /// Name of the crate the presented subgraph is reachable from.
+    Option<String>,
+    /// Options overriding the workspace configuration.
+    Options,
+  ),
+  /// Restore workspace manifest from backup.
+  Restore(
+    /// Path to the manifest file of the workspace.
+    String,
+    /// Number of the backup to be restored, counted from the newest one.
+    Option<usize>,
+    /// All questions will be answered with `yes` when `true`.
+    bool,
+  ),
+  /// Yank a version of workspace crates.
+  Yank(
+    /// Path to the manifest file of the workspace.
+    String,
+    /// Version to be yanked.
+    String,
+    /// Undo the yank when `true`.
+    bool,
+    /// Perform all checks without yanking crates when `true`.
+    bool,
+    /// All questions will be answered with `yes` when `true`.
+    bool,
+    /// Options overriding the workspace configuration.
+    Options,
+  ),
+  /// Manage owners of workspace crates.
+  Owners(
+    /// Path to the manifest file of the workspace.
+    String,
+    /// Operation performed on owners.
+    OwnersCommand,
+    /// Perform all checks without changing owners when `true`.
+    bool,
+    /// All questions will be answered with `yes` when `true`.
+    bool,
+    /// Options overriding the workspace configuration.
+    Options,
+  ),
+  /// Bump the workspace version.
+  Bump(
+    /// Path to the manifest file of the workspace.
+    String,
+    /// Change of the version.
+    Bump,
+    /// Only present the next version when `true`.
+    bool,
+    /// All questions will be answered with `yes` when `true`.
     bool,
+    /// Options overriding the workspace configuration.
+    Options,
+  ),
+  /// List crates changed since the previous release.
+  Changed(
+    /// Path to the manifest file of the workspace.
+    String,
+    /// Git reference crates are compared with, instead of the previous release tag.
+    Option<String>,
+    /// Options overriding the workspace configuration.
+    Options,
   ),
   /// Do nothing.
   Nothing,
@@ -32,6 +111,40 @@ enum Action {
 /// Parses CLI argument matches.
 fn get_matches() -> ArgMatches {
   command!()
+    .arg(
+      Arg::new("quiet")
+        .short('q')
+        .long("quiet")
+        .help("Report only warnings, errors and questions")
+        .action(ArgAction::SetTrue)
+        .default_value("false")
+        .default_missing_value("true")
+        .conflicts_with("verbose")
+        .global(true)
+        .display_order(101),
+    )
+    .arg(
+      Arg::new("verbose")
+        .short('v')
+        .long("verbose")
+        .help("Report details, like the effective configuration and debug traces")
+        .action(ArgAction::SetTrue)
+        .default_value("false")
+        .default_missing_value("true")
+        .global(true)
+        .display_order(102),
+    )
+    .arg(
+      Arg::new("color")
+        .long("color")
+        .help("Coloring of the output, overrides CARGO_TERM_COLOR and NO_COLOR")
+        .value_name("WHEN")
+        .value_parser(["auto", "always", "never"])
+        .num_args(1)
+        .action(ArgAction::Set)
+        .global(true)
+        .display_order(103),
+    )
     .subcommand(
       Command::new("publish")
         .about("Publish workspace crates")
@@ -72,7 +185,229 @@ fn get_matches() -> ArgMatches {
             .action(ArgAction::SetTrue)
             .default_value("false")
             .default_missing_value("true")
+            .overrides_with("no-fixed-versions")
             .display_order(4),
+        )
+        .arg(
+          Arg::new("no-fixed-versions")
+            .long("no-fixed-versions")
+            .help("Do not use fixed version numbers")
+            .action(ArgAction::SetTrue)
+            .overrides_with("fixed-versions")
+            .display_order(5),
+        )
+        .arg(
+          Arg::new("independent-versions")
+            .long("independent-versions")
+            .help("Allow crates to declare their own versions")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-independent-versions")
+            .display_order(6),
+        )
+        .arg(
+          Arg::new("no-independent-versions")
+            .long("no-independent-versions")
+            .help("Do not allow crates to declare their own versions")
+            .action(ArgAction::SetTrue)
+            .overrides_with("independent-versions")
+            .display_order(7),
+        )
+        .arg(
+          Arg::new("req-style")
+            .long("req-style")
+            .help("Version requirement operators, like 'cosmwasm-derive==,*=^'")
+            .value_name("CRATE=OPERATOR,...")
+            .num_args(1)
+            .value_parser(parse_req_styles)
+            .action(ArgAction::Append)
+            .display_order(8),
+        )
+        .arg(
+          Arg::new("hybrid")
+            .long("hybrid")
+            .help("Keep both local paths and versions of dependencies")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-hybrid")
+            .display_order(9),
+        )
+        .arg(
+          Arg::new("no-hybrid")
+            .long("no-hybrid")
+            .help("Do not keep both local paths and versions of dependencies")
+            .action(ArgAction::SetTrue)
+            .overrides_with("hybrid")
+            .display_order(10),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from publishing")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .display_order(11),
+        )
+        .arg(
+          Arg::new("changed")
+            .long("changed")
+            .help("Publish only crates changed since the previous release")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(12),
+        )
+        .arg(
+          Arg::new("since")
+            .long("since")
+            .help("Git reference crates are compared with, instead of the previous release tag")
+            .value_name("REF")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(13),
+        )
+        .arg(
+          Arg::new("registry")
+            .long("registry")
+            .help("Registry to publish to")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(14),
+        )
+        .arg(
+          Arg::new("tag-template")
+            .long("tag-template")
+            .help("Template of the release tag name")
+            .value_name("TEMPLATE")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(15),
+        )
+        .arg(
+          Arg::new("jobs")
+            .short('j')
+            .long("jobs")
+            .help("Number of crates published concurrently")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(16),
+        )
+        .arg(
+          Arg::new("retries")
+            .long("retries")
+            .help("Number of retries of cargo publish failed with a transient error")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u32))
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(17),
+        )
+        .arg(
+          Arg::new("timeout")
+            .long("timeout")
+            .help("Time limit of a single cargo publish command, in seconds")
+            .value_name("SECONDS")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(18),
+        )
+        .arg(
+          Arg::new("sort")
+            .long("sort")
+            .help("Order of crates that are ready to be published at the same time")
+            .value_parser(["alphabetical", "manifest"])
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(19),
+        )
+        .arg(
+          Arg::new("verify")
+            .long("verify")
+            .help("Verify contents of packaged crates before publishing")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-verify")
+            .display_order(20),
+        )
+        .arg(
+          Arg::new("no-verify")
+            .long("no-verify")
+            .help("Do not verify contents of packaged crates")
+            .action(ArgAction::SetTrue)
+            .overrides_with("verify")
+            .display_order(21),
+        )
+        .arg(
+          Arg::new("integration")
+            .long("integration")
+            .help("Build or test packaged crates together before publishing")
+            .value_name("MODE")
+            .value_parser(["none", "build", "test"])
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(22),
+        )
+        .arg(
+          Arg::new("semver-check")
+            .long("semver-check")
+            .help("Check the public API against the previous release before publishing")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-semver-check")
+            .display_order(23),
+        )
+        .arg(
+          Arg::new("no-semver-check")
+            .long("no-semver-check")
+            .help("Do not check the public API against the previous release")
+            .action(ArgAction::SetTrue)
+            .overrides_with("semver-check")
+            .display_order(24),
+        )
+        .arg(
+          Arg::new("update-lock")
+            .long("update-lock")
+            .help("Update the lock file after changing the workspace manifest")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-update-lock")
+            .display_order(25),
+        )
+        .arg(
+          Arg::new("no-update-lock")
+            .long("no-update-lock")
+            .help("Do not update the lock file")
+            .action(ArgAction::SetTrue)
+            .overrides_with("update-lock")
+            .display_order(26),
+        )
+        .arg(
+          Arg::new("locked")
+            .long("locked")
+            .help("Verify that the lock file is up to date before changing the workspace manifest")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-locked")
+            .display_order(27),
+        )
+        .arg(
+          Arg::new("no-locked")
+            .long("no-locked")
+            .help("Do not verify that the lock file is up to date")
+            .action(ArgAction::SetTrue)
+            .overrides_with("locked")
+            .display_order(28),
         ),
     )
     .subcommand(
@@ -106,6 +441,546 @@ fn get_matches() -> ArgMatches {
             .action(ArgAction::SetTrue)
             .default_value("false")
             .default_missing_value("true")
+            .overrides_with("no-fixed-versions")
+            .display_order(3),
+        )
+        .arg(
+          Arg::new("no-fixed-versions")
+            .long("no-fixed-versions")
+            .help("Do not use fixed version numbers")
+            .action(ArgAction::SetTrue)
+            .overrides_with("fixed-versions")
+            .display_order(4),
+        )
+        .arg(
+          Arg::new("independent-versions")
+            .long("independent-versions")
+            .help("Allow crates to declare their own versions")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-independent-versions")
+            .display_order(5),
+        )
+        .arg(
+          Arg::new("no-independent-versions")
+            .long("no-independent-versions")
+            .help("Do not allow crates to declare their own versions")
+            .action(ArgAction::SetTrue)
+            .overrides_with("independent-versions")
+            .display_order(6),
+        )
+        .arg(
+          Arg::new("req-style")
+            .long("req-style")
+            .help("Version requirement operators, like 'cosmwasm-derive==,*=^'")
+            .value_name("CRATE=OPERATOR,...")
+            .num_args(1)
+            .value_parser(parse_req_styles)
+            .action(ArgAction::Append)
+            .display_order(7),
+        )
+        .arg(
+          Arg::new("hybrid")
+            .long("hybrid")
+            .help("Keep both local paths and versions of dependencies")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-hybrid")
+            .display_order(8),
+        )
+        .arg(
+          Arg::new("no-hybrid")
+            .long("no-hybrid")
+            .help("Do not keep both local paths and versions of dependencies")
+            .action(ArgAction::SetTrue)
+            .overrides_with("hybrid")
+            .display_order(9),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from switching")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .display_order(10),
+        )
+        .arg(
+          Arg::new("update-lock")
+            .long("update-lock")
+            .help("Update the lock file after changing the workspace manifest")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-update-lock")
+            .display_order(11),
+        )
+        .arg(
+          Arg::new("no-update-lock")
+            .long("no-update-lock")
+            .help("Do not update the lock file")
+            .action(ArgAction::SetTrue)
+            .overrides_with("update-lock")
+            .display_order(12),
+        )
+        .arg(
+          Arg::new("locked")
+            .long("locked")
+            .help("Verify that the lock file is up to date before changing the workspace manifest")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .overrides_with("no-locked")
+            .display_order(13),
+        )
+        .arg(
+          Arg::new("no-locked")
+            .long("no-locked")
+            .help("Do not verify that the lock file is up to date")
+            .action(ArgAction::SetTrue)
+            .overrides_with("locked")
+            .display_order(14),
+        ),
+    )
+    .subcommand(
+      Command::new("graph")
+        .about("Export the dependency graph of workspace crates")
+        .display_order(3)
+        .arg(
+          Arg::new("dir")
+            .short('d')
+            .long("dir")
+            .help("Directory with workspace manifest")
+            .default_value(".")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(1),
+        )
+        .arg(
+          Arg::new("format")
+            .short('f')
+            .long("format")
+            .help("Output format")
+            .value_parser(["text", "dot", "mermaid"])
+            .default_value("text")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(2),
+        )
+        .arg(
+          Arg::new("order")
+            .long("order")
+            .help("Highlight the publishing order")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(3),
+        )
+        .arg(
+          Arg::new("unpublished")
+            .long("unpublished")
+            .help("Include crates with 'publish = false'")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(4),
+        )
+        .arg(
+          Arg::new("from")
+            .long("from")
+            .help("Present only the subgraph reachable from the crate")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(5),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from the graph")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .display_order(6),
+        )
+        .arg(
+          Arg::new("sort")
+            .long("sort")
+            .help("Order of crates that are ready to be published at the same time")
+            .value_parser(["alphabetical", "manifest"])
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(7),
+        ),
+    )
+    .subcommand(
+      Command::new("restore")
+        .about("Restore workspace manifest from backup")
+        .display_order(4)
+        .arg(
+          Arg::new("dir")
+            .short('d')
+            .long("dir")
+            .help("Directory with workspace manifest")
+            .default_value(".")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(1),
+        )
+        .arg(
+          Arg::new("backup")
+            .short('b')
+            .long("backup")
+            .help("Number of the backup to be restored, backups are only listed when not specified")
+            .value_name("NUMBER")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(2),
+        )
+        .arg(
+          Arg::new("accept-all")
+            .short('y')
+            .long("accept-all")
+            .help("Answer all questions with 'yes'")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(3),
+        ),
+    )
+    .subcommand(
+      Command::new("yank")
+        .about("Yank a version of workspace crates")
+        .display_order(5)
+        .arg(
+          Arg::new("dir")
+            .short('d')
+            .long("dir")
+            .help("Directory with workspace manifest")
+            .default_value(".")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(1),
+        )
+        .arg(
+          Arg::new("version")
+            .long("version")
+            .help("Version to be yanked")
+            .value_name("VERSION")
+            .required(true)
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(2),
+        )
+        .arg(
+          Arg::new("undo")
+            .long("undo")
+            .help("Undo a yank, putting the version back into the index")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(3),
+        )
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .help("Perform all checks without yanking")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(4),
+        )
+        .arg(
+          Arg::new("accept-all")
+            .short('y')
+            .long("accept-all")
+            .help("Answer all questions with 'yes'")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .display_order(5),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from yanking")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .display_order(6),
+        )
+        .arg(
+          Arg::new("registry")
+            .long("registry")
+            .help("Registry to yank from")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(7),
+        ),
+    )
+    .subcommand(
+      Command::new("owners")
+        .about("Manage owners of workspace crates")
+        .display_order(6)
+        .subcommand_required(true)
+        .arg(
+          Arg::new("dir")
+            .short('d')
+            .long("dir")
+            .help("Directory with workspace manifest")
+            .default_value(".")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .global(true)
+            .display_order(1),
+        )
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .help("Perform all checks without changing owners")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .global(true)
+            .display_order(2),
+        )
+        .arg(
+          Arg::new("accept-all")
+            .short('y')
+            .long("accept-all")
+            .help("Answer all questions with 'yes'")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .global(true)
+            .display_order(3),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from managing owners")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .global(true)
+            .display_order(4),
+        )
+        .arg(
+          Arg::new("registry")
+            .long("registry")
+            .help("Registry with published crates")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .global(true)
+            .display_order(5),
+        )
+        .subcommand(Command::new("list").about("List owners of all crates").display_order(1))
+        .subcommand(
+          Command::new("add").about("Add an owner to all crates").display_order(2).arg(
+            Arg::new("login")
+              .help("Login of the owner")
+              .value_name("LOGIN")
+              .required(true)
+              .num_args(1)
+              .action(ArgAction::Set),
+          ),
+        )
+        .subcommand(
+          Command::new("remove").about("Remove an owner from all crates").display_order(3).arg(
+            Arg::new("login")
+              .help("Login of the owner")
+              .value_name("LOGIN")
+              .required(true)
+              .num_args(1)
+              .action(ArgAction::Set),
+          ),
+        ),
+    )
+    .subcommand(
+      Command::new("bump")
+        .about("Bump the workspace version")
+        .display_order(7)
+        .subcommand_required(true)
+        .arg(
+          Arg::new("dir")
+            .short('d')
+            .long("dir")
+            .help("Directory with workspace manifest")
+            .default_value(".")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .global(true)
+            .display_order(1),
+        )
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .help("Present the next version without changing the manifest")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .global(true)
+            .display_order(2),
+        )
+        .arg(
+          Arg::new("accept-all")
+            .short('y')
+            .long("accept-all")
+            .help("Answer all questions with 'yes'")
+            .action(ArgAction::SetTrue)
+            .default_value("false")
+            .default_missing_value("true")
+            .global(true)
+            .display_order(3),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from synchronizing versions in hybrid mode")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .global(true)
+            .display_order(4),
+        )
+        .arg(
+          Arg::new("registry")
+            .long("registry")
+            .help("Registry the workspace is published to")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .global(true)
+            .display_order(5),
+        )
+        .arg(
+          Arg::new("hybrid")
+            .long("hybrid")
+            .help("Keep both local paths and versions of dependencies")
+            .action(ArgAction::SetTrue)
+            .overrides_with("no-hybrid")
+            .global(true)
+            .display_order(6),
+        )
+        .arg(
+          Arg::new("no-hybrid")
+            .long("no-hybrid")
+            .help("Do not keep both local paths and versions of dependencies")
+            .action(ArgAction::SetTrue)
+            .overrides_with("hybrid")
+            .global(true)
+            .display_order(7),
+        )
+        .arg(
+          Arg::new("update-lock")
+            .long("update-lock")
+            .help("Update the lock file after changing the workspace manifest")
+            .action(ArgAction::SetTrue)
+            .overrides_with("no-update-lock")
+            .global(true)
+            .display_order(8),
+        )
+        .arg(
+          Arg::new("no-update-lock")
+            .long("no-update-lock")
+            .help("Do not update the lock file")
+            .action(ArgAction::SetTrue)
+            .overrides_with("update-lock")
+            .global(true)
+            .display_order(9),
+        )
+        .arg(
+          Arg::new("locked")
+            .long("locked")
+            .help("Verify that the lock file is up to date before changing the workspace manifest")
+            .action(ArgAction::SetTrue)
+            .overrides_with("no-locked")
+            .global(true)
+            .display_order(10),
+        )
+        .arg(
+          Arg::new("no-locked")
+            .long("no-locked")
+            .help("Do not verify that the lock file is up to date")
+            .action(ArgAction::SetTrue)
+            .overrides_with("locked")
+            .global(true)
+            .display_order(11),
+        )
+        .subcommand(
+          Command::new("pre")
+            .about("Bump to the next pre-release")
+            .display_order(1)
+            .arg(
+              Arg::new("channel")
+                .long("channel")
+                .help("Pre-release channel, like 'alpha', 'beta' or 'rc'")
+                .value_name("CHANNEL")
+                .default_value("rc")
+                .num_args(1)
+                .action(ArgAction::Set)
+                .display_order(12),
+            )
+            .arg(
+              Arg::new("next")
+                .long("next")
+                .help("Part of the version incremented when the version is not a pre-release")
+                .value_parser(["major", "minor", "patch"])
+                .default_value("patch")
+                .num_args(1)
+                .action(ArgAction::Set)
+                .display_order(13),
+            ),
+        )
+        .subcommand(Command::new("release").about("Bump to the final release, stripping the pre-release").display_order(2)),
+    )
+    .subcommand(
+      Command::new("changed")
+        .about("List crates changed since the previous release")
+        .display_order(8)
+        .arg(
+          Arg::new("dir")
+            .short('d')
+            .long("dir")
+            .help("Directory with workspace manifest")
+            .default_value(".")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(1),
+        )
+        .arg(
+          Arg::new("since")
+            .long("since")
+            .help("Git reference crates are compared with, instead of the previous release tag")
+            .value_name("REF")
+            .num_args(1)
+            .action(ArgAction::Set)
+            .display_order(2),
+        )
+        .arg(
+          Arg::new("exclude")
+            .short('e')
+            .long("exclude")
+            .help("Exclude crate from checking")
+            .value_name("CRATE")
+            .num_args(1)
+            .action(ArgAction::Append)
+            .display_order(3),
+        )
+        .arg(
+          Arg::new("tag-template")
+            .long("tag-template")
+            .help("Template of the release tag name")
+            .value_name("TEMPLATE")
+            .num_args(1)
+            .action(ArgAction::Set)
             .display_order(4),
         ),
     )
@@ -116,19 +991,136 @@ fn get_matches() -> ArgMatches {
 /// and returns an action related to a valid argument.
 fn get_cli_action() -> Action {
   let matches = get_matches();
+  let verbosity = if match_boolean(&matches, "quiet") {
+    Verbosity::Quiet
+  } else if match_boolean(&matches, "verbose") {
+    Verbosity::Verbose
+  } else {
+    Verbosity::Normal
+  };
+  output::init(verbosity, match_optional_string(&matches, "color").and_then(|color| ColorChoice::new(&color)));
   match matches.subcommand() {
     Some(("publish", matches)) => {
       let dir = match_string(matches, "dir");
       let dry_run = match_boolean(matches, "dry-run");
       let accept_all = match_boolean(matches, "accept-all");
-      let fixed_version = match_boolean(matches, "fixed-versions");
-      return Action::Publish(dir, dry_run, accept_all, fixed_version);
+      let options = Options {
+        fixed_versions: match_negatable(matches, "fixed-versions"),
+        independent_versions: match_negatable(matches, "independent-versions"),
+        req_styles: match_req_styles(matches),
+        hybrid: match_negatable(matches, "hybrid"),
+        exclude: match_optional_strings(matches, "exclude"),
+        registry: match_optional_string(matches, "registry"),
+        tag_template: match_optional_string(matches, "tag-template"),
+        jobs: matches.get_one::<u64>("jobs").map(|jobs| *jobs as usize),
+        retries: matches.get_one::<u32>("retries").copied(),
+        timeout: matches.get_one::<u64>("timeout").copied(),
+        sort: match_optional_string(matches, "sort").and_then(|sort| SortOrder::new(&sort)),
+        verify: match_negatable(matches, "verify"),
+        integration: match_optional_string(matches, "integration").and_then(|integration| Integration::new(&integration)),
+        semver_check: match_negatable(matches, "semver-check"),
+        update_lock: match_negatable(matches, "update-lock"),
+        locked: match_negatable(matches, "locked"),
+        changed: match_boolean(matches, "changed"),
+        since: match_optional_string(matches, "since"),
+      };
+      return Action::Publish(dir, dry_run, accept_all, options);
     }
     Some(("develop", matches)) => {
       let dir = match_string(matches, "dir");
       let accept_all = match_boolean(matches, "accept-all");
-      let fixed_version = match_boolean(matches, "fixed-versions");
-      return Action::Develop(dir, accept_all, fixed_version);
+      let options = Options {
+        fixed_versions: match_negatable(matches, "fixed-versions"),
+        independent_versions: match_negatable(matches, "independent-versions"),
+        req_styles: match_req_styles(matches),
+        hybrid: match_negatable(matches, "hybrid"),
+        exclude: match_optional_strings(matches, "exclude"),
+        update_lock: match_negatable(matches, "update-lock"),
+        locked: match_negatable(matches, "locked"),
+        ..Default::default()
+      };
+      return Action::Develop(dir, accept_all, options);
+    }
+    Some(("graph", matches)) => {
+      let dir = match_string(matches, "dir");
+      let format = GraphFormat::new(&match_string(matches, "format"));
+      let show_order = match_boolean(matches, "order");
+      let show_unpublished = match_boolean(matches, "unpublished");
+      let from = match_optional_string(matches, "from");
+      let options = Options {
+        exclude: match_optional_strings(matches, "exclude"),
+        sort: match_optional_string(matches, "sort").and_then(|sort| SortOrder::new(&sort)),
+        ..Default::default()
+      };
+      return Action::Graph(dir, format, show_order, show_unpublished, from, options);
+    }
+    Some(("restore", matches)) => {
+      let dir = match_string(matches, "dir");
+      let backup = matches.get_one::<u64>("backup").map(|backup| *backup as usize);
+      let accept_all = match_boolean(matches, "accept-all");
+      return Action::Restore(dir, backup, accept_all);
+    }
+    Some(("yank", matches)) => {
+      let dir = match_string(matches, "dir");
+      let version = match_string(matches, "version");
+      let undo = match_boolean(matches, "undo");
+      let dry_run = match_boolean(matches, "dry-run");
+      let accept_all = match_boolean(matches, "accept-all");
+      let options = Options {
+        exclude: match_optional_strings(matches, "exclude"),
+        registry: match_optional_string(matches, "registry"),
+        ..Default::default()
+      };
+      return Action::Yank(dir, version, undo, dry_run, accept_all, options);
+    }
+    Some(("bump", matches)) => {
+      let (bump, matches) = match matches.subcommand() {
+        Some(("pre", matches)) => (
+          Bump::Pre(match_string(matches, "channel"), Level::new(&match_string(matches, "next")).unwrap_or_default()),
+          matches,
+        ),
+        Some((_, matches)) => (Bump::Release, matches),
+        None => return Action::Nothing,
+      };
+      let dir = match_string(matches, "dir");
+      let dry_run = match_boolean(matches, "dry-run");
+      let accept_all = match_boolean(matches, "accept-all");
+      let options = Options {
+        exclude: match_optional_strings(matches, "exclude"),
+        registry: match_optional_string(matches, "registry"),
+        hybrid: match_negatable(matches, "hybrid"),
+        update_lock: match_negatable(matches, "update-lock"),
+        locked: match_negatable(matches, "locked"),
+        ..Default::default()
+      };
+      return Action::Bump(dir, bump, dry_run, accept_all, options);
+    }
+    Some(("changed", matches)) => {
+      let dir = match_string(matches, "dir");
+      let since = match_optional_string(matches, "since");
+      let options = Options {
+        exclude: match_optional_strings(matches, "exclude"),
+        tag_template: match_optional_string(matches, "tag-template"),
+        ..Default::default()
+      };
+      return Action::Changed(dir, since, options);
+    }
+    Some(("owners", matches)) => {
+      let (command, matches) = match matches.subcommand() {
+        Some(("add", matches)) => (OwnersCommand::Add(match_string(matches, "login")), matches),
+        Some(("remove", matches)) => (OwnersCommand::Remove(match_string(matches, "login")), matches),
+        Some((_, matches)) => (OwnersCommand::List, matches),
+        None => return Action::Nothing,
+      };
+      let dir = match_string(matches, "dir");
+      let dry_run = match_boolean(matches, "dry-run");
+      let accept_all = match_boolean(matches, "accept-all");
+      let options = Options {
+        exclude: match_optional_strings(matches, "exclude"),
+        registry: match_optional_string(matches, "registry"),
+        ..Default::default()
+      };
+      return Action::Owners(dir, command, dry_run, accept_all, options);
     }
     _ => {}
   }
@@ -137,13 +1129,13 @@ fn get_cli_action() -> Action {
 
 pub fn do_action() {
   fn error_message(reason: UniverError) -> Text {
-    auto().bold().red().s("error").reset().s(": ").s(reason.to_string())
+    auto_stderr().bold().red().s("error").reset().s(": ").s(reason.to_string())
   }
 
   match get_cli_action() {
-    Action::Publish(dir, dry_run, accept_all, fixed_version) => {
+    Action::Publish(dir, dry_run, accept_all, options) => {
       // Publish workspace crates.
-      match publish::publish(Path::new(&dir), dry_run, accept_all, fixed_version) {
+      match publish::publish(Path::new(&dir), dry_run, accept_all, &options) {
         Ok(()) => {}
         Err(reason) => {
           eprintln!("{}", error_message(reason));
@@ -151,9 +1143,69 @@ pub fn do_action() {
         }
       }
     }
-    Action::Develop(dir, accept_all, fixed_version) => {
+    Action::Develop(dir, accept_all, options) => {
       // Switch workspace crates to local development mode.
-      match develop::develop(Path::new(&dir), accept_all, fixed_version) {
+      match develop::develop(Path::new(&dir), accept_all, &options) {
+        Ok(()) => {}
+        Err(reason) => {
+          eprintln!("{}", error_message(reason));
+          std::process::exit(1);
+        }
+      }
+    }
+    Action::Graph(dir, format, show_order, show_unpublished, from, options) => {
+      // Export the dependency graph of workspace crates.
+      match graph::graph(Path::new(&dir), format, show_order, show_unpublished, from.as_deref(), &options) {
+        Ok(()) => {}
+        Err(reason) => {
+          eprintln!("{}", error_message(reason));
+          std::process::exit(1);
+        }
+      }
+    }
+    Action::Restore(dir, backup, accept_all) => {
+      // Restore workspace manifest from backup.
+      match restore::restore(Path::new(&dir), backup, accept_all) {
+        Ok(()) => {}
+        Err(reason) => {
+          eprintln!("{}", error_message(reason));
+          std::process::exit(1);
+        }
+      }
+    }
+    Action::Yank(dir, version, undo, dry_run, accept_all, options) => {
+      // Yank a version of workspace crates.
+      match yank::yank(Path::new(&dir), &version, undo, dry_run, accept_all, &options) {
+        Ok(()) => {}
+        Err(reason) => {
+          eprintln!("{}", error_message(reason));
+          std::process::exit(1);
+        }
+      }
+    }
+    Action::Owners(dir, command, dry_run, accept_all, options) => {
+      // Manage owners of workspace crates.
+      match owners::owners(Path::new(&dir), &command, dry_run, accept_all, &options) {
+        Ok(()) => {}
+        Err(reason) => {
+          eprintln!("{}", error_message(reason));
+          std::process::exit(1);
+        }
+      }
+    }
+    Action::Bump(dir, bump, dry_run, accept_all, options) => {
+      // Bump the workspace version.
+      match bump::bump(Path::new(&dir), &bump, dry_run, accept_all, &options) {
+        Ok(()) => {}
+        Err(reason) => {
+          eprintln!("{}", error_message(reason));
+          std::process::exit(1);
+        }
+      }
+    }
+    Action::Changed(dir, since, options) => {
+      // List crates changed since the previous release.
+      match changed::changed(Path::new(&dir), since.as_deref(), &options) {
         Ok(()) => {}
         Err(reason) => {
           eprintln!("{}", error_message(reason));
@@ -176,3 +1228,29 @@ fn match_string(matches: &ArgMatches, name: &str) -> String {
 fn match_boolean(matches: &ArgMatches, name: &str) -> bool {
   matches.get_flag(name)
 }
+
+/// Matches a boolean argument that can be negated with `--no-` prefix, `None` when none of them is present.
+fn match_negatable(matches: &ArgMatches, name: &str) -> Option<bool> {
+  if match_boolean(matches, name) {
+    Some(true)
+  } else if match_boolean(matches, &format!("no-{}", name)) {
+    Some(false)
+  } else {
+    None
+  }
+}
+
+/// Matches an optional string argument.
+fn match_optional_string(matches: &ArgMatches, name: &str) -> Option<String> {
+  matches.get_one::<String>(name).map(|value| value.trim().to_string())
+}
+
+/// Matches an optional list of string arguments.
+fn match_optional_strings(matches: &ArgMatches, name: &str) -> Option<Vec<String>> {
+  matches.get_many::<String>(name).map(|values| values.map(|value| value.trim().to_string()).collect())
+}
+
+/// Matches an optional list of requirement styles.
+fn match_req_styles(matches: &ArgMatches) -> Option<Vec<(String, ReqStyle)>> {
+  matches.get_many::<Vec<(String, ReqStyle)>>("req-style").map(|values| values.flatten().cloned().collect())
+}
@@ -0,0 +1,515 @@
+//! # Configuration defined in `[workspace.metadata.univer]` table
+
+use crate::errors::{Result, univer_error};
+use crate::hooks::{HookKind, Hooks};
+use crate::output::{auto, info};
+use antex::StyledText;
+
+/// Default maximum size of a single file in the packaged crate (1 MiB).
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1_048_576;
+
+/// Default number of retries of `cargo publish` failed with a transient error.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Default delay before the first retry of `cargo publish`, in seconds, doubled with every retry.
+pub const DEFAULT_RETRY_DELAY: u64 = 5;
+
+/// Default template of the release tag name.
+pub const DEFAULT_TAG_TEMPLATE: &str = "v{{version}}";
+
+/// Order used to break ties between crates that are ready to be published at the same time.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum SortOrder {
+  /// Crates are ordered alphabetically by name.
+  Alphabetical,
+  /// Crates are ordered like entries in `[workspace.dependencies]` table.
+  #[default]
+  Manifest,
+}
+
+impl SortOrder {
+  /// Creates the sort order from its name.
+  pub fn new(name: &str) -> Option<Self> {
+    match name {
+      "alphabetical" => Some(SortOrder::Alphabetical),
+      "manifest" => Some(SortOrder::Manifest),
+      _ => None,
+    }
+  }
+}
+
+impl std::fmt::Display for SortOrder {
+  /// Implementation of [Display](std::fmt::Display) trait for [SortOrder].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SortOrder::Alphabetical => write!(f, "alphabetical"),
+      SortOrder::Manifest => write!(f, "manifest"),
+    }
+  }
+}
+
+/// Checks performed on packaged crates, built together as a workspace, before publishing.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Integration {
+  /// No integration checks are performed.
+  #[default]
+  None,
+  /// Packaged crates are built.
+  Build,
+  /// Packaged crates are built and tested.
+  Test,
+}
+
+impl Integration {
+  /// Creates the integration mode from its name.
+  pub fn new(name: &str) -> Option<Self> {
+    match name {
+      "none" => Some(Integration::None),
+      "build" => Some(Integration::Build),
+      "test" => Some(Integration::Test),
+      _ => None,
+    }
+  }
+}
+
+impl std::fmt::Display for Integration {
+  /// Implementation of [Display](std::fmt::Display) trait for [Integration].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Integration::None => write!(f, "none"),
+      Integration::Build => write!(f, "build"),
+      Integration::Test => write!(f, "test"),
+    }
+  }
+}
+
+/// Operator of the version requirement written into `[workspace.dependencies]` table.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ReqStyle {
+  /// Plain version, like `"3.0.2"`.
+  #[default]
+  Plain,
+  /// Caret requirement, like `"^3.0.2"`.
+  Caret,
+  /// Tilde requirement, like `"~3.0.2"`.
+  Tilde,
+  /// Exact requirement, like `"=3.0.2"`.
+  Exact,
+}
+
+impl ReqStyle {
+  /// All requirement styles.
+  pub const ALL: [ReqStyle; 4] = [ReqStyle::Plain, ReqStyle::Caret, ReqStyle::Tilde, ReqStyle::Exact];
+
+  /// Creates the requirement style from its operator, empty operator means plain version.
+  pub fn new(operator: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|style| style.operator() == operator)
+  }
+
+  /// Returns the operator preceding the version number.
+  pub fn operator(&self) -> &'static str {
+    match self {
+      ReqStyle::Plain => "",
+      ReqStyle::Caret => "^",
+      ReqStyle::Tilde => "~",
+      ReqStyle::Exact => "=",
+    }
+  }
+}
+
+/// Parses the list of requirement styles, like `cosmwasm-derive==,*=^`.
+///
+/// Each entry consists of the crate name (or `*` for all crates), `=` and the operator.
+pub fn parse_req_styles(value: &str) -> std::result::Result<Vec<(String, ReqStyle)>, String> {
+  let mut req_styles = vec![];
+  for entry in value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+    let Some((name, operator)) = entry.split_once('=') else {
+      return Err(format!("expected CRATE=OPERATOR, found '{}'", entry));
+    };
+    let Some(req_style) = ReqStyle::new(operator.trim()) else {
+      return Err(format!("unknown requirement operator '{}' for '{}', expected one of: =, ^, ~ or none", operator, name));
+    };
+    req_styles.push((name.trim().to_string(), req_style));
+  }
+  Ok(req_styles)
+}
+
+/// Options passed from the command line, overriding the workspace configuration.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+  /// Use fixed version numbers.
+  pub fixed_versions: Option<bool>,
+  /// Allow crates to declare their own versions.
+  pub independent_versions: Option<bool>,
+  /// Requirement styles for crates, `*` stands for all crates.
+  pub req_styles: Option<Vec<(String, ReqStyle)>>,
+  /// Keep both local paths and versions of dependencies.
+  pub hybrid: Option<bool>,
+  /// Names of crates excluded from processing.
+  pub exclude: Option<Vec<String>>,
+  /// Name of the registry to publish to.
+  pub registry: Option<String>,
+  /// Template of the release tag name.
+  pub tag_template: Option<String>,
+  /// Maximum number of crates published concurrently.
+  pub jobs: Option<usize>,
+  /// Number of retries of `cargo publish` failed with a transient error.
+  pub retries: Option<u32>,
+  /// Time limit of a single `cargo publish` command, in seconds.
+  pub timeout: Option<u64>,
+  /// Order used to break ties in publishing order.
+  pub sort: Option<SortOrder>,
+  /// Verify contents of packaged crates before publishing.
+  pub verify: Option<bool>,
+  /// Build or test packaged crates together before publishing.
+  pub integration: Option<Integration>,
+  /// Check the public API against the previous release before publishing.
+  pub semver_check: Option<bool>,
+  /// Update the lock file after the workspace manifest is changed.
+  pub update_lock: Option<bool>,
+  /// Verify that the lock file is up to date before the workspace manifest is changed.
+  pub locked: Option<bool>,
+  /// Publish only crates changed since the previous release.
+  pub changed: bool,
+  /// Git reference the crates are compared with, instead of the previous release tag.
+  pub since: Option<String>,
+}
+
+impl Options {
+  /// Returns `true` when requirement styles are set from the command line.
+  pub fn has_req_styles(&self) -> bool {
+    self.fixed_versions.is_some() || self.req_styles.is_some()
+  }
+}
+
+/// Effective configuration of the workspace.
+#[derive(Debug, Clone)]
+pub struct Config {
+  /// Use fixed version numbers (`"=3.0.2"`) instead of plain ones (`"3.0.2"`).
+  pub fixed_versions: bool,
+  /// Allow crates to declare their own `version` instead of inheriting the workspace one.
+  pub independent_versions: bool,
+  /// Requirement styles for crates, `*` stands for all crates, overrides `fixed_versions`.
+  pub req_styles: Vec<(String, ReqStyle)>,
+  /// Keep both `path` and `version` of dependencies, with versions in sync with crate versions (hybrid mode).
+  pub hybrid: bool,
+  /// Names of crates excluded from processing.
+  pub exclude: Vec<String>,
+  /// Name of the registry to publish to, `None` means the default registry.
+  pub registry: Option<String>,
+  /// Template of the release tag name.
+  pub tag_template: String,
+  /// Maximum number of crates published concurrently, `1` means sequential publishing.
+  ///
+  /// Concurrently published crates are built in separate target directories, without reusing the workspace build.
+  pub jobs: usize,
+  /// Number of retries of `cargo publish` failed with a transient error, like a network failure or rate limit.
+  pub retries: u32,
+  /// Delay before the first retry of `cargo publish`, in seconds, doubled with every next retry.
+  pub retry_delay: u64,
+  /// Time limit of a single `cargo publish` command, in seconds, hung commands are killed; `None` means no limit.
+  pub timeout: Option<u64>,
+  /// Order used to break ties in publishing order.
+  pub sort: SortOrder,
+  /// Verify contents of packaged crates before publishing.
+  pub verify: bool,
+  /// Maximum size of a single file in the packaged crate, in bytes.
+  pub max_file_size: u64,
+  /// Patterns of files that must not be included in packaged crates, like `*.wasm` or `.env`.
+  pub forbidden: Vec<String>,
+  /// Build or test packaged crates together, patched with each other, before publishing.
+  pub integration: Integration,
+  /// Compare the public API of crates with the previous release and check if the version bump is large enough.
+  pub semver_check: bool,
+  /// Update `Cargo.lock` for workspace crates after the workspace manifest is changed.
+  pub update_lock: bool,
+  /// Verify that `cargo metadata --locked` passes before the workspace manifest is changed.
+  pub locked: bool,
+  /// Hooks defined for the whole workspace.
+  pub hooks: Hooks,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      fixed_versions: false,
+      independent_versions: false,
+      req_styles: vec![],
+      hybrid: false,
+      exclude: vec![],
+      registry: None,
+      tag_template: DEFAULT_TAG_TEMPLATE.to_string(),
+      jobs: 1,
+      retries: DEFAULT_RETRIES,
+      retry_delay: DEFAULT_RETRY_DELAY,
+      timeout: None,
+      sort: SortOrder::default(),
+      verify: false,
+      max_file_size: DEFAULT_MAX_FILE_SIZE,
+      forbidden: vec![],
+      integration: Integration::default(),
+      semver_check: false,
+      update_lock: true,
+      locked: false,
+      hooks: Hooks::default(),
+    }
+  }
+}
+
+impl Config {
+  /// Loads the configuration from the `[workspace.metadata.univer]` table
+  /// and applies options passed from the command line.
+  pub fn load(univer: Option<&toml::Value>, options: &Options) -> Result<Self> {
+    let mut config = Self {
+      hooks: Hooks::load(univer, "[workspace.metadata.univer] table")?,
+      ..Default::default()
+    };
+    if let Some(univer) = univer {
+      if let Some(value) = univer.get("fixed-versions") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'fixed-versions' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.fixed_versions = value;
+      }
+      if let Some(value) = univer.get("independent-versions") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'independent-versions' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.independent_versions = value;
+      }
+      if let Some(value) = univer.get("hybrid") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'hybrid' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.hybrid = value;
+      }
+      if let Some(value) = univer.get("req-style") {
+        let Some(table) = value.as_table() else {
+          return Err(univer_error!("'req-style' is not a table in [workspace.metadata.univer] table"));
+        };
+        for (name, value) in table {
+          let Some(req_style) = value.as_str().and_then(ReqStyle::new) else {
+            return Err(univer_error!(
+              "invalid requirement operator for '{}' in 'req-style' in [workspace.metadata.univer] table",
+              name
+            ));
+          };
+          config.req_styles.push((name.clone(), req_style));
+        }
+      }
+      if let Some(value) = univer.get("exclude") {
+        let Some(values) = value.as_array() else {
+          return Err(univer_error!("'exclude' is not an array in [workspace.metadata.univer] table"));
+        };
+        for value in values {
+          let Some(value) = value.as_str() else {
+            return Err(univer_error!("'exclude' must contain only strings in [workspace.metadata.univer] table"));
+          };
+          config.exclude.push(value.to_string());
+        }
+      }
+      if let Some(value) = univer.get("registry") {
+        let Some(value) = value.as_str() else {
+          return Err(univer_error!("'registry' is not a string in [workspace.metadata.univer] table"));
+        };
+        config.registry = Some(value.to_string());
+      }
+      if let Some(value) = univer.get("tag-template") {
+        let Some(value) = value.as_str() else {
+          return Err(univer_error!("'tag-template' is not a string in [workspace.metadata.univer] table"));
+        };
+        config.tag_template = value.to_string();
+      }
+      if let Some(value) = univer.get("jobs") {
+        let Some(value) = value.as_integer().and_then(|value| usize::try_from(value).ok()).filter(|value| *value > 0) else {
+          return Err(univer_error!("'jobs' is not a positive integer in [workspace.metadata.univer] table"));
+        };
+        config.jobs = value;
+      }
+      if let Some(value) = univer.get("retries") {
+        let Some(value) = value.as_integer().and_then(|value| u32::try_from(value).ok()) else {
+          return Err(univer_error!("'retries' is not a non-negative integer in [workspace.metadata.univer] table"));
+        };
+        config.retries = value;
+      }
+      if let Some(value) = univer.get("retry-delay") {
+        let Some(value) = value.as_integer().and_then(|value| u64::try_from(value).ok()) else {
+          return Err(univer_error!("'retry-delay' is not a non-negative integer in [workspace.metadata.univer] table"));
+        };
+        config.retry_delay = value;
+      }
+      if let Some(value) = univer.get("timeout") {
+        let Some(value) = value.as_integer().and_then(|value| u64::try_from(value).ok()).filter(|value| *value > 0) else {
+          return Err(univer_error!("'timeout' is not a positive integer in [workspace.metadata.univer] table"));
+        };
+        config.timeout = Some(value);
+      }
+      if let Some(value) = univer.get("sort") {
+        let Some(value) = value.as_str().and_then(SortOrder::new) else {
+          return Err(univer_error!("'sort' must be 'alphabetical' or 'manifest' in [workspace.metadata.univer] table"));
+        };
+        config.sort = value;
+      }
+      if let Some(value) = univer.get("verify") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'verify' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.verify = value;
+      }
+      if let Some(value) = univer.get("max-file-size") {
+        let Some(value) = value.as_integer().and_then(|value| u64::try_from(value).ok()) else {
+          return Err(univer_error!("'max-file-size' is not a non-negative integer in [workspace.metadata.univer] table"));
+        };
+        config.max_file_size = value;
+      }
+      if let Some(value) = univer.get("forbidden") {
+        let Some(values) = value.as_array() else {
+          return Err(univer_error!("'forbidden' is not an array in [workspace.metadata.univer] table"));
+        };
+        for value in values {
+          let Some(value) = value.as_str() else {
+            return Err(univer_error!("'forbidden' must contain only strings in [workspace.metadata.univer] table"));
+          };
+          config.forbidden.push(value.to_string());
+        }
+      }
+      if let Some(value) = univer.get("integration") {
+        let Some(value) = value.as_str().and_then(Integration::new) else {
+          return Err(univer_error!("'integration' must be 'none', 'build' or 'test' in [workspace.metadata.univer] table"));
+        };
+        config.integration = value;
+      }
+      if let Some(value) = univer.get("semver-check") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'semver-check' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.semver_check = value;
+      }
+      if let Some(value) = univer.get("update-lock") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'update-lock' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.update_lock = value;
+      }
+      if let Some(value) = univer.get("locked") {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'locked' is not a boolean in [workspace.metadata.univer] table"));
+        };
+        config.locked = value;
+      }
+    }
+    // Options passed from the command line take precedence.
+    if let Some(fixed_versions) = options.fixed_versions {
+      config.fixed_versions = fixed_versions;
+    }
+    if let Some(independent_versions) = options.independent_versions {
+      config.independent_versions = independent_versions;
+    }
+    if let Some(req_styles) = &options.req_styles {
+      config.req_styles = req_styles.clone();
+    }
+    if let Some(hybrid) = options.hybrid {
+      config.hybrid = hybrid;
+    }
+    if let Some(exclude) = &options.exclude {
+      config.exclude = exclude.clone();
+    }
+    if let Some(registry) = &options.registry {
+      config.registry = Some(registry.clone());
+    }
+    if let Some(tag_template) = &options.tag_template {
+      config.tag_template = tag_template.clone();
+    }
+    if let Some(jobs) = options.jobs {
+      config.jobs = jobs;
+    }
+    if let Some(retries) = options.retries {
+      config.retries = retries;
+    }
+    if let Some(timeout) = options.timeout {
+      config.timeout = Some(timeout);
+    }
+    if let Some(sort) = options.sort {
+      config.sort = sort;
+    }
+    if let Some(verify) = options.verify {
+      config.verify = verify;
+    }
+    if let Some(integration) = options.integration {
+      config.integration = integration;
+    }
+    if let Some(semver_check) = options.semver_check {
+      config.semver_check = semver_check;
+    }
+    if let Some(update_lock) = options.update_lock {
+      config.update_lock = update_lock;
+    }
+    if let Some(locked) = options.locked {
+      config.locked = locked;
+    }
+    Ok(config)
+  }
+
+  /// Returns `true` when the crate with specified name is excluded from processing.
+  pub fn is_excluded(&self, name: &str) -> bool {
+    self.exclude.iter().any(|excluded| excluded == name)
+  }
+
+  /// Returns the requirement style for the crate with specified name.
+  ///
+  /// The style defined for the crate takes precedence over the one defined for all crates (`*`),
+  /// otherwise the style follows `fixed_versions` setting.
+  pub fn req_style(&self, name: &str) -> ReqStyle {
+    let find = |pattern: &str| self.req_styles.iter().rev().find(|(name, _)| name == pattern).map(|(_, req_style)| *req_style);
+    find(name)
+      .or_else(|| find("*"))
+      .unwrap_or(if self.fixed_versions { ReqStyle::Exact } else { ReqStyle::Plain })
+  }
+
+  /// Returns the name of the release tag for the specified crate and version.
+  pub fn tag_name(&self, crate_name: &str, version: &str) -> String {
+    self.tag_template.replace("{{crate_name}}", crate_name).replace("{{version}}", version)
+  }
+
+  /// Prints the effective configuration.
+  pub fn report(&self) {
+    let none = || auto().italic().s("none").reset().to_string();
+    info!();
+    info!("Configuration:");
+    let line = |key: &str, value: String| info!("  {:<20}  {}", key, value);
+    line("fixed-versions", auto().bold().s(self.fixed_versions).reset().to_string());
+    line("independent-versions", auto().bold().s(self.independent_versions).reset().to_string());
+    line("hybrid", auto().bold().s(self.hybrid).reset().to_string());
+    line(
+      "req-style",
+      if self.req_styles.is_empty() {
+        none()
+      } else {
+        self
+          .req_styles
+          .iter()
+          .map(|(name, req_style)| format!("{}={}", name, req_style.operator()))
+          .collect::<Vec<String>>()
+          .join(", ")
+      },
+    );
+    line("exclude", if self.exclude.is_empty() { none() } else { self.exclude.join(", ") });
+    line("registry", self.registry.clone().unwrap_or_else(none));
+    line("tag-template", self.tag_template.clone());
+    line("jobs", self.jobs.to_string());
+    line("retries", self.retries.to_string());
+    line("retry-delay", format!("{}s", self.retry_delay));
+    line("timeout", self.timeout.map(|timeout| format!("{}s", timeout)).unwrap_or_else(none));
+    line("sort", self.sort.to_string());
+    line("verify", auto().bold().s(self.verify).reset().to_string());
+    line("max-file-size", self.max_file_size.to_string());
+    line("forbidden", if self.forbidden.is_empty() { none() } else { self.forbidden.join(", ") });
+    line("integration", self.integration.to_string());
+    line("semver-check", auto().bold().s(self.semver_check).reset().to_string());
+    line("update-lock", auto().bold().s(self.update_lock).reset().to_string());
+    line("locked", auto().bold().s(self.locked).reset().to_string());
+    for kind in [HookKind::PrePublish, HookKind::PostPublish, HookKind::PreDevelop, HookKind::PostDevelop] {
+      line(kind.key(), self.hooks.get(kind).map(|hook| hook.to_string()).unwrap_or_else(none));
+    }
+  }
+}
@@ -1,26 +1,64 @@
+use crate::config::Options;
 use crate::errors::{Result, univer_error};
+use crate::hooks::HookKind;
+use crate::journal::Journal;
 use crate::model::Workspace;
-use crate::utils;
+use crate::{backup, hooks, lock, output, utils};
 use std::path::Path;
 
 /// Switches workspace crates to local development mode.
-pub fn develop(manifest_dir: &Path, _accept_all: bool, fixed_version: bool) -> Result<()> {
-  let workspace = Workspace::load(manifest_dir)?;
+pub fn develop(manifest_dir: &Path, _accept_all: bool, options: &Options) -> Result<()> {
+  let workspace = Workspace::load(manifest_dir, options)?;
+  let config = workspace.config();
+  if output::is_verbose() {
+    config.report();
+  }
+  // The lock file must be up to date before anything is changed.
+  if config.locked {
+    lock::verify(&workspace)?;
+  }
+  // Execute hooks before switching to development mode.
+  let workspace_envs = hooks::workspace_envs(&workspace, &workspace.members, false);
+  hooks::run(&config.hooks, HookKind::PreDevelop, "workspace", workspace.root().as_ref(), &workspace_envs, false)?;
+  for member in &workspace.members {
+    let member_envs = hooks::member_envs(&workspace, member, false);
+    hooks::run(&member.hooks, HookKind::PreDevelop, &member.name, member.manifest_dir.as_ref(), &member_envs, false)?;
+  }
   let mut manifest_content = utils::read_file(workspace.manifest_path())?;
+  let mut journal = Journal::load(&workspace)?;
   for member in &workspace.members {
-    let dependency_with_version = &member.dependency_with_version(fixed_version);
+    if config.hybrid {
+      // In hybrid mode local paths are added and versions are synchronized with crate versions.
+      manifest_content = member.sync_dependency(&manifest_content, config.req_style(&member.name))?;
+      continue;
+    }
     let dependency_with_path = &member.dependency_with_path();
-    if manifest_content.contains(dependency_with_version) {
-      manifest_content = manifest_content.replace(dependency_with_version, dependency_with_path);
+    // Any version requirement is recognized, its style is remembered in the journal to be restored when publishing.
+    if let Some((dependency_with_version, req_style)) = member.find_dependency_with_version(&manifest_content) {
+      manifest_content = member.replace_in_dependency(&manifest_content, &dependency_with_version, dependency_with_path);
+      journal.set_requirement(&member.name, req_style);
     } else {
       return Err(univer_error!(
-        "dependency '{}' with version '{}' not found or has an invalid format, expected '{}'",
+        "dependency '{}' with version '{}' not found or has an invalid format, expected '{} = {{ {} }}'",
         member.name,
         member.version,
-        dependency_with_version
+        member.name,
+        member.dependency_with_version(config.req_style(&member.name))
       ));
     }
   }
+  backup::create(&workspace.univer_dir(), workspace.manifest_path(), "develop")?;
   utils::write_file(workspace.manifest_path(), manifest_content)?;
+  journal.save()?;
+  // Update the lock file to reflect changed sources of workspace crates.
+  if config.update_lock {
+    lock::report(&lock::update(&workspace)?);
+  }
+  // Execute hooks after switching to development mode.
+  for member in &workspace.members {
+    let member_envs = hooks::member_envs(&workspace, member, false);
+    hooks::run(&member.hooks, HookKind::PostDevelop, &member.name, member.manifest_dir.as_ref(), &member_envs, false)?;
+  }
+  hooks::run(&config.hooks, HookKind::PostDevelop, "workspace", workspace.root().as_ref(), &workspace_envs, false)?;
   Ok(())
 }
@@ -0,0 +1,248 @@
+//! # Export of the dependency graph of workspace members
+
+use crate::config::Options;
+use crate::errors::{Result, univer_error};
+use crate::model::{DependencyKind, Member, Workspace};
+use crate::utils;
+use petgraph::Direction;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::{Dfs, EdgeRef, Reversed};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Output format of the dependency graph.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GraphFormat {
+  /// Graphviz DOT language.
+  Dot,
+  /// Mermaid flowchart.
+  Mermaid,
+  /// Plain text tree.
+  Text,
+}
+
+impl GraphFormat {
+  /// Creates a graph format from its name, unknown names default to plain text tree.
+  pub fn new(name: &str) -> Self {
+    match name {
+      "dot" => GraphFormat::Dot,
+      "mermaid" => GraphFormat::Mermaid,
+      _ => GraphFormat::Text,
+    }
+  }
+}
+
+/// Node of the exported graph.
+struct Node {
+  /// Package name.
+  name: String,
+  /// Position in the publishing order (1-based), when highlighted.
+  order: Option<usize>,
+  /// Flag indicating if the package may be published.
+  publish: bool,
+  /// Dependencies as pairs of node position and dependency kind, sorted by name.
+  dependencies: Vec<(usize, DependencyKind)>,
+}
+
+impl Node {
+  /// Returns the label of the node.
+  fn label(&self) -> String {
+    let mut label = self.name.clone();
+    if let Some(order) = self.order {
+      let _ = write!(label, " #{}", order);
+    }
+    if !self.publish {
+      label.push_str(" (publish = false)");
+    }
+    label
+  }
+}
+
+/// Prints the dependency graph of workspace members.
+pub fn graph(manifest_dir: &Path, format: GraphFormat, show_order: bool, show_unpublished: bool, from: Option<&str>, options: &Options) -> Result<()> {
+  let workspace = Workspace::load(manifest_dir, options)?;
+  // Select packages to be presented in the graph.
+  let packages = workspace
+    .packages
+    .iter()
+    .filter(|package| (show_unpublished && !package.publish) || workspace.members.iter().any(|member| member.name == package.name))
+    .cloned()
+    .collect::<Vec<Member>>();
+  let graph = utils::dependency_graph(&packages);
+  // Select the subgraph reachable from the given crate.
+  let reachable = match from {
+    Some(name) => {
+      let Some(start) = graph.node_indices().find(|node_index| graph[*node_index].name == name) else {
+        return Err(univer_error!("crate '{}' not found in workspace", name));
+      };
+      Some(reachable_from(&graph, start))
+    }
+    None => None,
+  };
+  let publish_order = if show_order {
+    utils::sort(workspace.members.clone(), workspace.config().sort)?
+      .into_iter()
+      .enumerate()
+      .map(|(index, member)| (member.name, index + 1))
+      .collect()
+  } else {
+    HashMap::new()
+  };
+  let nodes = collect_nodes(&graph, reachable.as_ref(), &publish_order);
+  let output = match format {
+    GraphFormat::Dot => to_dot(&nodes, from),
+    GraphFormat::Mermaid => to_mermaid(&nodes, from),
+    GraphFormat::Text => to_text(&nodes, from),
+  };
+  print!("{}", output);
+  Ok(())
+}
+
+/// Returns indexes of nodes reachable from the start node by following dependencies.
+fn reachable_from(graph: &DiGraph<Member, DependencyKind>, start: NodeIndex) -> HashSet<NodeIndex> {
+  let mut reachable = HashSet::new();
+  // Edges lead from dependency to dependent, so dependencies are found in reversed graph.
+  let reversed = Reversed(graph);
+  let mut dfs = Dfs::new(reversed, start);
+  while let Some(node_index) = dfs.next(reversed) {
+    reachable.insert(node_index);
+  }
+  reachable
+}
+
+/// Collects graph nodes sorted by name, with dependencies limited to presented nodes.
+fn collect_nodes(graph: &DiGraph<Member, DependencyKind>, reachable: Option<&HashSet<NodeIndex>>, publish_order: &HashMap<String, usize>) -> Vec<Node> {
+  let mut node_indexes = graph
+    .node_indices()
+    .filter(|node_index| reachable.is_none_or(|reachable| reachable.contains(node_index)))
+    .collect::<Vec<NodeIndex>>();
+  node_indexes.sort_by(|a, b| graph[*a].name.cmp(&graph[*b].name));
+  let positions = node_indexes
+    .iter()
+    .enumerate()
+    .map(|(position, node_index)| (*node_index, position))
+    .collect::<HashMap<NodeIndex, usize>>();
+  node_indexes
+    .iter()
+    .map(|node_index| {
+      let member = &graph[*node_index];
+      let mut dependencies = BTreeSet::new();
+      for edge in graph.edges_directed(*node_index, Direction::Incoming) {
+        if let Some(position) = positions.get(&edge.source()) {
+          dependencies.insert((*position, kind_rank(*edge.weight())));
+        }
+      }
+      Node {
+        name: member.name.clone(),
+        order: publish_order.get(&member.name).copied(),
+        publish: member.publish,
+        dependencies: dependencies.into_iter().map(|(position, rank)| (position, KINDS[rank])).collect(),
+      }
+    })
+    .collect()
+}
+
+/// Dependency kinds in presentation order.
+const KINDS: [DependencyKind; 3] = [DependencyKind::Normal, DependencyKind::Build, DependencyKind::Development];
+
+/// Returns the position of the dependency kind in presentation order.
+fn kind_rank(kind: DependencyKind) -> usize {
+  KINDS.iter().position(|k| *k == kind).unwrap_or_default()
+}
+
+/// Renders the graph in Graphviz DOT language.
+fn to_dot(nodes: &[Node], from: Option<&str>) -> String {
+  let mut output = String::new();
+  let _ = writeln!(output, "digraph workspace {{");
+  let _ = writeln!(output, "  node [shape=box];");
+  for node in nodes {
+    let mut attributes = vec![format!("label=\"{}\"", node.label())];
+    if !node.publish {
+      attributes.push("style=dashed".to_string());
+      attributes.push("color=gray".to_string());
+    }
+    if from == Some(node.name.as_str()) {
+      attributes.push("penwidth=2".to_string());
+    }
+    let _ = writeln!(output, "  \"{}\" [{}];", node.name, attributes.join(", "));
+  }
+  for node in nodes {
+    for (position, kind) in &node.dependencies {
+      let _ = writeln!(output, "  \"{}\" -> \"{}\" [label=\"{}\"];", node.name, nodes[*position].name, kind);
+    }
+  }
+  let _ = writeln!(output, "}}");
+  output
+}
+
+/// Renders the graph as Mermaid flowchart.
+fn to_mermaid(nodes: &[Node], from: Option<&str>) -> String {
+  let mut output = String::new();
+  let _ = writeln!(output, "graph TD");
+  for (position, node) in nodes.iter().enumerate() {
+    let _ = writeln!(output, "  n{}[\"{}\"]", position, node.label());
+  }
+  for (position, node) in nodes.iter().enumerate() {
+    for (dependency_position, kind) in &node.dependencies {
+      let _ = writeln!(output, "  n{} -->|{}| n{}", position, kind, dependency_position);
+    }
+  }
+  let unpublished = nodes
+    .iter()
+    .enumerate()
+    .filter(|(_, node)| !node.publish)
+    .map(|(position, _)| format!("n{}", position))
+    .collect::<Vec<String>>();
+  if !unpublished.is_empty() {
+    let _ = writeln!(output, "  classDef unpublished stroke-dasharray: 5 5,color:gray");
+    let _ = writeln!(output, "  class {} unpublished", unpublished.join(","));
+  }
+  if let Some(position) = nodes.iter().position(|node| from == Some(node.name.as_str())) {
+    let _ = writeln!(output, "  classDef start stroke-width:3px");
+    let _ = writeln!(output, "  class n{} start", position);
+  }
+  output
+}
+
+/// Renders the graph as plain text tree.
+fn to_text(nodes: &[Node], from: Option<&str>) -> String {
+  // Roots are the starting crate or crates no other crate depends on.
+  let roots = match from {
+    Some(name) => nodes.iter().position(|node| node.name == name).into_iter().collect::<Vec<usize>>(),
+    None => (0..nodes.len())
+      .filter(|position| !nodes.iter().any(|node| node.dependencies.iter().any(|(p, _)| p == position)))
+      .collect(),
+  };
+  let mut output = String::new();
+  let mut visited = HashSet::new();
+  for root in roots {
+    let _ = writeln!(output, "{}", nodes[root].label());
+    visited.insert(root);
+    write_subtree(&mut output, nodes, root, "", &mut visited);
+  }
+  output
+}
+
+/// Renders dependencies of the node, subtrees already presented are marked with `(*)`.
+fn write_subtree(output: &mut String, nodes: &[Node], position: usize, indent: &str, visited: &mut HashSet<usize>) {
+  let dependencies = &nodes[position].dependencies;
+  for (index, (dependency_position, kind)) in dependencies.iter().enumerate() {
+    let last = index == dependencies.len() - 1;
+    let node = &nodes[*dependency_position];
+    let kind = if *kind == DependencyKind::Normal { String::new() } else { format!(" [{}]", kind) };
+    let repeated = !visited.insert(*dependency_position) && !node.dependencies.is_empty();
+    let _ = writeln!(
+      output,
+      "{}{}{}{}{}",
+      indent,
+      if last { "└── " } else { "├── " },
+      node.label(),
+      kind,
+      if repeated { " (*)" } else { "" }
+    );
+    if !repeated {
+      write_subtree(output, nodes, *dependency_position, &format!("{}{}", indent, if last { "    " } else { "│   " }), visited);
+    }
+  }
+}
@@ -0,0 +1,166 @@
+//! # Hooks executed before and after publishing or switching to development mode
+
+use crate::errors::{Result, univer_error};
+use crate::model::{Member, Workspace};
+use crate::output::{auto, info};
+use crate::utils;
+use antex::StyledText;
+use std::path::Path;
+
+/// Kinds of supported hooks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HookKind {
+  /// Executed before publishing.
+  PrePublish,
+  /// Executed after publishing.
+  PostPublish,
+  /// Executed before switching to local development mode.
+  PreDevelop,
+  /// Executed after switching to local development mode.
+  PostDevelop,
+}
+
+impl HookKind {
+  /// Returns the key under which the hook is defined in the manifest.
+  pub fn key(&self) -> &'static str {
+    match self {
+      HookKind::PrePublish => "pre-publish",
+      HookKind::PostPublish => "post-publish",
+      HookKind::PreDevelop => "pre-develop",
+      HookKind::PostDevelop => "post-develop",
+    }
+  }
+}
+
+/// Single command executed as a hook.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Hook {
+  /// Program to be executed.
+  pub program: String,
+  /// Arguments passed to the program.
+  pub args: Vec<String>,
+}
+
+impl std::fmt::Display for Hook {
+  /// Implementation of [Display](std::fmt::Display) trait for [Hook].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.program)?;
+    for arg in &self.args {
+      write!(f, " {}", arg)?;
+    }
+    Ok(())
+  }
+}
+
+/// Hooks defined in `[workspace.metadata.univer]` or `[package.metadata.univer]` table.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Hooks {
+  pre_publish: Option<Hook>,
+  post_publish: Option<Hook>,
+  pre_develop: Option<Hook>,
+  post_develop: Option<Hook>,
+}
+
+impl Hooks {
+  /// Loads hooks from the `univer` metadata table, `owner` is used only in error messages.
+  ///
+  /// A hook given as a string is split on whitespace, quoting is not supported,
+  /// so arguments containing whitespace must be given in the array form.
+  pub fn load(univer: Option<&toml::Value>, owner: &str) -> Result<Self> {
+    let Some(univer) = univer else {
+      return Ok(Self::default());
+    };
+    let load_hook = |kind: HookKind| -> Result<Option<Hook>> {
+      let key = kind.key();
+      let Some(value) = univer.get(key) else {
+        return Ok(None);
+      };
+      let mut items = if let Some(line) = value.as_str() {
+        line.split_whitespace().map(|item| item.to_string()).collect::<Vec<String>>()
+      } else if let Some(array) = value.as_array() {
+        let mut items = vec![];
+        for item in array {
+          let Some(item) = item.as_str() else {
+            return Err(univer_error!("'{}' hook must contain only strings in {}", key, owner));
+          };
+          items.push(item.to_string());
+        }
+        items
+      } else {
+        return Err(univer_error!("'{}' hook must be a string or an array of strings in {}", key, owner));
+      };
+      if items.is_empty() {
+        return Err(univer_error!("'{}' hook is empty in {}", key, owner));
+      }
+      let program = items.remove(0);
+      Ok(Some(Hook { program, args: items }))
+    };
+    Ok(Self {
+      pre_publish: load_hook(HookKind::PrePublish)?,
+      post_publish: load_hook(HookKind::PostPublish)?,
+      pre_develop: load_hook(HookKind::PreDevelop)?,
+      post_develop: load_hook(HookKind::PostDevelop)?,
+    })
+  }
+
+  /// Returns the hook of the specified kind.
+  pub fn get(&self, kind: HookKind) -> Option<&Hook> {
+    match kind {
+      HookKind::PrePublish => self.pre_publish.as_ref(),
+      HookKind::PostPublish => self.post_publish.as_ref(),
+      HookKind::PreDevelop => self.pre_develop.as_ref(),
+      HookKind::PostDevelop => self.post_develop.as_ref(),
+    }
+  }
+}
+
+/// Executes the hook of the specified kind (if defined) in the given directory.
+///
+/// Environment variables are passed to the hook, the hook's non-zero exit status
+/// is reported as an error, so the whole process is aborted.
+pub fn run(hooks: &Hooks, kind: HookKind, owner: &str, dir: &Path, envs: &[(String, String)], dry_run: bool) -> Result<()> {
+  let Some(hook) = hooks.get(kind) else {
+    return Ok(());
+  };
+  info!(
+    "\n{} {} {}",
+    auto().bold().bg_cyan().s(format!("  {}  ", kind.key().to_uppercase())).reset(),
+    auto().bold().blue().s(owner).reset(),
+    hook
+  );
+  if dry_run {
+    return Ok(());
+  }
+  // Programs given with a path are resolved relatively to the directory the hook is executed in.
+  let program = if hook.program.contains('/') || hook.program.contains('\\') {
+    dir.join(&hook.program).to_string_lossy().to_string()
+  } else {
+    hook.program.clone()
+  };
+  utils::execute_command(&program, &hook.args, dir, envs).map_err(|e| univer_error!("'{}' hook failed for {}, reason: {}", kind.key(), owner, e))
+}
+
+/// Returns environment variables passed to workspace hooks.
+pub fn workspace_envs(workspace: &Workspace, members: &[Member], dry_run: bool) -> Vec<(String, String)> {
+  vec![
+    ("UNIVER_WORKSPACE_ROOT".to_string(), workspace.root().to_string()),
+    ("UNIVER_VERSION".to_string(), workspace.version().to_string()),
+    (
+      "UNIVER_CRATES".to_string(),
+      members.iter().map(|member| member.name.as_str()).collect::<Vec<&str>>().join(" "),
+    ),
+    ("UNIVER_DRY_RUN".to_string(), dry_run.to_string()),
+  ]
+}
+
+/// Returns environment variables passed to crate hooks.
+pub fn member_envs(workspace: &Workspace, member: &Member, dry_run: bool) -> Vec<(String, String)> {
+  vec![
+    ("UNIVER_WORKSPACE_ROOT".to_string(), workspace.root().to_string()),
+    ("UNIVER_VERSION".to_string(), workspace.version().to_string()),
+    ("UNIVER_CRATE_NAME".to_string(), member.name.clone()),
+    ("UNIVER_CRATE_VERSION".to_string(), member.version.clone()),
+    ("UNIVER_CRATE_PATH".to_string(), member.manifest_dir.to_string()),
+    ("UNIVER_DRY_RUN".to_string(), dry_run.to_string()),
+  ]
+}
@@ -0,0 +1,123 @@
+//! # Building packaged crates together before publishing
+
+use crate::config::Integration;
+use crate::errors::{Result, univer_error};
+use crate::model::{Member, Workspace};
+use crate::output::{self, auto, info};
+use crate::{backup, utils};
+use antex::StyledText;
+use cargo_metadata::camino::Utf8Path;
+
+/// Name of the directory with the integration workspace in the univer working directory.
+const INTEGRATION_DIR_NAME: &str = "integration";
+
+/// Name of the directory with extracted packaged crates in the integration workspace.
+const CRATES_DIR_NAME: &str = "crates";
+
+/// Packages crates, extracts them into the integration workspace and builds (or tests) them offline.
+///
+/// Packaged crates depend on each other like published ones, the registry is patched
+/// with extracted crates, so nothing has to be published yet. Crates are checked in the order
+/// of publishing, names of crates that failed to build (or test) are returned.
+///
+/// The workspace manifest content is the one to be published, the manifest file is changed
+/// only for packaging and restored afterwards.
+pub fn check(workspace: &Workspace, members: &[Member], manifest_content: &str) -> Result<Vec<String>> {
+  let config = workspace.config();
+  let integration_dir = workspace.univer_dir().join(INTEGRATION_DIR_NAME);
+  let crates_dir = integration_dir.join(CRATES_DIR_NAME);
+  package(workspace, members, manifest_content, &integration_dir)?;
+  // Extract packaged crates, crates left from previous checks are removed.
+  if crates_dir.exists() {
+    std::fs::remove_dir_all(&crates_dir).map_err(|e| univer_error!("failed to remove directory {}, reason: {}", crates_dir, e))?;
+  }
+  std::fs::create_dir_all(&crates_dir).map_err(|e| univer_error!("failed to create directory {}, reason: {}", crates_dir, e))?;
+  for member in members {
+    let crate_file = integration_dir.join("target").join("package").join(format!("{}.crate", crate_dir_name(member)));
+    extract(&crate_file, &crates_dir).map_err(|e| univer_error!("extracting crate '{}' failed, reason: {}", member.name, e))?;
+  }
+  // Create the integration workspace, with the registry patched with extracted crates.
+  let paths = members
+    .iter()
+    .map(|member| format!("{}/{}", CRATES_DIR_NAME, crate_dir_name(member)))
+    .collect::<Vec<String>>();
+  let mut content = String::from("[workspace]\nmembers = [\n");
+  for path in &paths {
+    content.push_str(&format!("  \"{}\",\n", path));
+  }
+  content.push_str("]\nresolver = \"2\"\n\n");
+  content.push_str(&format!("[patch.{}]\n", config.registry.as_deref().unwrap_or("crates-io")));
+  for (member, path) in members.iter().zip(&paths) {
+    content.push_str(&format!("{} = {{ path = \"{}\" }}\n", member.name, path));
+  }
+  utils::write_file(integration_dir.join(utils::RUST_MANIFEST_NAME), content)?;
+  // Versions of external dependencies are the same as in the workspace.
+  let lock_path = workspace.root().join("Cargo.lock");
+  if lock_path.exists() {
+    utils::write_file(integration_dir.join("Cargo.lock"), utils::read_file(&lock_path)?)?;
+  }
+  // Build (and test) crates one by one, failures of all crates are reported.
+  let color = format!("--color={}", output::cargo_color());
+  let mut failed = vec![];
+  info!("Integration:");
+  for member in members {
+    let mut commands = vec!["build"];
+    if config.integration == Integration::Test {
+      commands.push("test");
+    }
+    let status = commands
+      .iter()
+      .try_for_each(|command| utils::execute_command("cargo", [command, "--offline", &color, "--package", &member.name], &integration_dir, &[]));
+    info!(
+      "{}  {}  {}",
+      auto().bold().blue().s(&member.name).reset(),
+      auto().bold().green().s('v').s(&member.version).reset(),
+      match status {
+        Ok(()) => auto().green().s("ok").reset(),
+        Err(_) => auto().red().s("failed").reset(),
+      }
+    );
+    if status.is_err() {
+      failed.push(member.name.clone());
+    }
+  }
+  Ok(failed)
+}
+
+/// Packages all crates at once, with versions of dependencies added to workspace dependencies.
+fn package(workspace: &Workspace, members: &[Member], manifest_content: &str, integration_dir: &Utf8Path) -> Result<()> {
+  let config = workspace.config();
+  // Cargo refuses to package crates with dependencies having only path.
+  let mut content = manifest_content.to_string();
+  for member in members {
+    content = member.sync_dependency(&content, config.req_style(&member.name))?;
+  }
+  // Back up the workspace manifest before it is changed, in case it is not restored (like when interrupted).
+  let original_content = utils::read_file(workspace.manifest_path())?;
+  backup::create(&workspace.univer_dir(), workspace.manifest_path(), "integration")?;
+  utils::write_file(workspace.manifest_path(), &content)?;
+  let target_dir = integration_dir.join("target");
+  let color = format!("--color={}", output::cargo_color());
+  let mut args = vec!["package", "--no-verify", "--allow-dirty", &color, "--target-dir", target_dir.as_str()];
+  if let Some(registry) = &config.registry {
+    args.extend(["--registry", registry.as_str()]);
+  }
+  for member in members {
+    args.extend(["--package", member.name.as_str()]);
+  }
+  let result = utils::execute_command("cargo", &args, workspace.root(), &[]);
+  // The manifest is restored even when packaging failed.
+  utils::write_file(workspace.manifest_path(), original_content)?;
+  result.map_err(|e| univer_error!("packaging crates failed, reason: {}", e))
+}
+
+/// Extracts the packaged crate (gzipped tarball) into the directory.
+fn extract(crate_file: &Utf8Path, dir: &Utf8Path) -> std::io::Result<()> {
+  let file = std::fs::File::open(crate_file)?;
+  tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(dir)
+}
+
+/// Returns the name of the directory with the extracted crate, the same as the crate file stem.
+fn crate_dir_name(member: &Member) -> String {
+  format!("{}-{}", member.name, member.version)
+}
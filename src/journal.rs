@@ -0,0 +1,99 @@
+//! # Journal of workspace manifest changes, kept between `develop` and `publish`
+
+use crate::config::ReqStyle;
+use crate::errors::{Result, univer_error};
+use crate::model::Workspace;
+use crate::utils;
+use cargo_metadata::camino::Utf8PathBuf;
+use std::collections::BTreeMap;
+
+/// Name of the directory with the journal in the workspace root.
+///
+/// The journal is kept outside the target directory, so it survives `cargo clean`.
+/// The directory ignores itself, so it never appears as untracked in the repository.
+const JOURNAL_DIR_NAME: &str = ".univer";
+
+/// Name of the git ignore file in the journal directory.
+const GITIGNORE_FILE_NAME: &str = ".gitignore";
+
+/// Name of the journal file.
+const JOURNAL_FILE_NAME: &str = "journal.toml";
+
+/// Journal of workspace manifest changes.
+#[derive(Debug, Default, Clone)]
+pub struct Journal {
+  /// Path to the journal file.
+  path: Utf8PathBuf,
+  /// Requirement styles of dependencies replaced with local paths, by crate name.
+  requirements: BTreeMap<String, ReqStyle>,
+}
+
+impl Journal {
+  /// Loads the journal of the workspace, missing journal file means an empty journal.
+  pub fn load(workspace: &Workspace) -> Result<Self> {
+    let path = workspace.root().join(JOURNAL_DIR_NAME).join(JOURNAL_FILE_NAME);
+    let mut requirements = BTreeMap::new();
+    if path.exists() {
+      let journal_toml = utils::parse_toml(&path)?;
+      if let Some(table) = journal_toml.get("requirements").and_then(|value| value.as_table()) {
+        for (name, value) in table {
+          let Some(req_style) = value.as_str().and_then(ReqStyle::new) else {
+            return Err(univer_error!("invalid requirement operator for '{}' in journal {}", name, path));
+          };
+          requirements.insert(name.clone(), req_style);
+        }
+      }
+    }
+    Ok(Self { path, requirements })
+  }
+
+  /// Returns the remembered requirement style of the crate.
+  pub fn requirement(&self, name: &str) -> Option<ReqStyle> {
+    self.requirements.get(name).copied()
+  }
+
+  /// Remembers the requirement style of the crate.
+  pub fn set_requirement(&mut self, name: &str, req_style: ReqStyle) {
+    self.requirements.insert(name.to_string(), req_style);
+  }
+
+  /// Forgets the requirement style of the crate.
+  pub fn remove_requirement(&mut self, name: &str) {
+    self.requirements.remove(name);
+  }
+
+  /// Saves the journal, an empty journal is removed together with its directory, when the directory is empty.
+  pub fn save(&self) -> Result<()> {
+    if self.requirements.is_empty() {
+      if self.path.exists() {
+        std::fs::remove_file(&self.path).map_err(|e| univer_error!("failed to remove journal {}, reason: {}", self.path, e))?;
+        if let Some(parent) = self.path.parent() {
+          // The directory is removed only when nothing else than the git ignore file is left in it.
+          let gitignore_path = parent.join(GITIGNORE_FILE_NAME);
+          let names = std::fs::read_dir(parent)
+            .map(|entries| entries.flatten().map(|entry| entry.file_name()).collect::<Vec<_>>())
+            .unwrap_or_default();
+          if names.iter().all(|name| name == GITIGNORE_FILE_NAME) {
+            let _ = std::fs::remove_file(gitignore_path);
+            let _ = std::fs::remove_dir(parent);
+          }
+        }
+      }
+      return Ok(());
+    }
+    if let Some(parent) = self.path.parent() {
+      std::fs::create_dir_all(parent).map_err(|e| univer_error!("failed to create directory {}, reason: {}", parent, e))?;
+      let gitignore_path = parent.join(GITIGNORE_FILE_NAME);
+      if !gitignore_path.exists() {
+        utils::write_file(&gitignore_path, "*\n")?;
+      }
+    }
+    let mut requirements = toml::Table::new();
+    for (name, req_style) in &self.requirements {
+      requirements.insert(name.clone(), toml::Value::String(req_style.operator().to_string()));
+    }
+    let mut journal = toml::Table::new();
+    journal.insert("requirements".to_string(), toml::Value::Table(requirements));
+    utils::write_file(&self.path, journal.to_string())
+  }
+}
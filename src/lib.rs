@@ -1,12 +1,37 @@
 #![doc = include_str!("../docs/README.md")]
 
+mod backup;
+mod bump;
+mod changed;
 mod cli;
+mod config;
 mod develop;
 mod errors;
+mod graph;
+mod hooks;
+mod integration;
+mod journal;
+mod lock;
 mod model;
+mod output;
+mod owners;
 mod publish;
+mod replacements;
+mod restore;
+mod retry;
+mod semver_check;
+mod summary;
 mod utils;
+mod verify;
+mod yank;
 
+pub use bump::{Bump, Level, bump};
+pub use changed::changed;
 pub use cli::do_action;
+pub use config::Options;
 pub use develop::develop;
+pub use graph::{GraphFormat, graph};
+pub use owners::{OwnersCommand, owners};
 pub use publish::publish;
+pub use restore::restore;
+pub use yank::yank;
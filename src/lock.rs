@@ -0,0 +1,110 @@
+//! # Updating the lock file after the workspace manifest is changed
+
+use crate::errors::{Result, UniverError, univer_error};
+use crate::model::Workspace;
+use crate::output::{self, auto, info};
+use crate::utils;
+use antex::StyledText;
+use cargo_metadata::MetadataCommand;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Name of the lock file.
+const LOCK_FILE_NAME: &str = "Cargo.lock";
+
+/// Difference of a single package between two versions of the lock file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockChange {
+  /// Package was added.
+  Added,
+  /// Package was removed.
+  Removed,
+}
+
+/// Locked package, with its name, version and source (`None` for workspace crates).
+pub type LockedPackage = (String, String, Option<String>);
+
+/// Locked packages, the same name and version can be locked from different sources.
+pub type LockedPackages = BTreeSet<LockedPackage>;
+
+/// Changes of locked packages.
+pub type LockChanges = BTreeMap<LockedPackage, LockChange>;
+
+/// Verifies that the lock file is up to date with the workspace manifest, using `cargo metadata --locked`.
+///
+/// Called before the workspace manifest is changed, so a stale lock file is reported
+/// instead of being silently fixed by the update.
+pub fn verify(workspace: &Workspace) -> Result<()> {
+  MetadataCommand::new()
+    .manifest_path(workspace.manifest_path())
+    .other_options(vec!["--locked".to_string()])
+    .exec()
+    .map(|_| ())
+    .map_err(|e| UniverError::new(format!("{} is not up to date, reason: {}", LOCK_FILE_NAME, e)))
+}
+
+/// Returns packages locked in the workspace lock file, missing lock file has no packages.
+pub fn packages(workspace: &Workspace) -> Result<LockedPackages> {
+  load_packages(workspace.root().join(LOCK_FILE_NAME))
+}
+
+/// Updates the lock file for workspace crates, without reporting changes.
+pub fn refresh(workspace: &Workspace) -> Result<()> {
+  utils::execute_command("cargo", ["update", "--workspace", &format!("--color={}", output::cargo_color())], workspace.root(), &[])
+    .map_err(|e| univer_error!("updating {} failed, reason: {}", LOCK_FILE_NAME, e))
+}
+
+/// Updates the lock file for workspace crates and returns the changes of locked packages.
+pub fn update(workspace: &Workspace) -> Result<LockChanges> {
+  let before = packages(workspace)?;
+  refresh(workspace)?;
+  Ok(diff(&before, &packages(workspace)?))
+}
+
+/// Returns changes between two versions of locked packages.
+pub fn diff(before: &LockedPackages, after: &LockedPackages) -> LockChanges {
+  let removed = before.difference(after).map(|package| (package.clone(), LockChange::Removed));
+  let added = after.difference(before).map(|package| (package.clone(), LockChange::Added));
+  removed.chain(added).collect()
+}
+
+/// Prints changes of locked packages.
+pub fn report(changes: &LockChanges) {
+  info!();
+  if changes.is_empty() {
+    info!("{}  no changes", auto().bold().s(LOCK_FILE_NAME).reset());
+    return;
+  }
+  info!("{}:", auto().bold().s(LOCK_FILE_NAME).reset());
+  for ((name, version, source), change) in changes {
+    let sign = match change {
+      LockChange::Added => auto().green().s('+').reset(),
+      LockChange::Removed => auto().red().s('-').reset(),
+    };
+    let source = source.as_deref().unwrap_or("local");
+    info!(
+      "{} {}  {}  {}",
+      sign,
+      auto().bold().blue().s(name).reset(),
+      auto().bold().green().s('v').s(version).reset(),
+      source
+    );
+  }
+}
+
+/// Loads locked packages, missing lock file has no packages.
+fn load_packages(lock_path: impl AsRef<std::path::Path>) -> Result<LockedPackages> {
+  let lock_path = lock_path.as_ref();
+  let mut packages = BTreeSet::new();
+  if !lock_path.exists() {
+    return Ok(packages);
+  }
+  let lock_toml = utils::parse_toml(lock_path)?;
+  for package in lock_toml.get("package").and_then(|p| p.as_array()).into_iter().flatten() {
+    let (Some(name), Some(version)) = (package.get("name").and_then(|n| n.as_str()), package.get("version").and_then(|v| v.as_str())) else {
+      continue;
+    };
+    let source = package.get("source").and_then(|s| s.as_str()).map(|s| s.to_string());
+    packages.insert((name.to_string(), version.to_string(), source));
+  }
+  Ok(packages)
+}
@@ -1,14 +1,71 @@
+use crate::config::{Config, Options, ReqStyle};
 use crate::errors::{Result, UniverError, univer_error};
-use crate::utils;
+use crate::hooks::Hooks;
+use crate::output::debug;
 use crate::utils::RUST_MANIFEST_NAME;
+use crate::{bump, utils};
 use cargo_metadata::MetadataCommand;
 use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use regex::Regex;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::LazyLock;
+
+/// Header of the `[workspace.dependencies]` table.
+static DEPENDENCIES_HEADER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^[ \t]*\[workspace\.dependencies\][ \t]*(#.*)?$").unwrap());
+
+/// Header of any table.
+static HEADER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^[ \t]*\[").unwrap());
+
+/// Dependency entry defined as an inline table on a single line, the name is captured.
+static DEPENDENCY_ENTRY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?m)^[ \t]*([A-Za-z0-9_-]+|"[^"\n]*")[ \t]*=[ \t]*\{[^}\n]*\}"#).unwrap());
+
+/// Key-value pair with version requirement in the dependency entry, the requirement operator and the version are captured.
+static VERSION_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"[{,][ \t]*(version[ \t]*=[ \t]*"([=^~]?)([^"]*)")"#).unwrap());
+
+/// Key-value pair with local path in the dependency entry, the path is captured.
+static PATH_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"[{,][ \t]*(path[ \t]*=[ \t]*"(?:\./)?([^"]*?)/?")"#).unwrap());
+
+/// Kind of the dependency.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+  /// Dependency defined in `[dependencies]` section.
+  #[default]
+  Normal,
+  /// Dependency defined in `[dev-dependencies]` section.
+  Development,
+  /// Dependency defined in `[build-dependencies]` section.
+  Build,
+}
+
+impl std::fmt::Display for DependencyKind {
+  /// Implementation of [Display](std::fmt::Display) trait for [DependencyKind].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      DependencyKind::Normal => write!(f, "normal"),
+      DependencyKind::Development => write!(f, "dev"),
+      DependencyKind::Build => write!(f, "build"),
+    }
+  }
+}
+
+impl From<cargo_metadata::DependencyKind> for DependencyKind {
+  /// Converts the dependency kind reported by `cargo metadata`.
+  fn from(kind: cargo_metadata::DependencyKind) -> Self {
+    match kind {
+      cargo_metadata::DependencyKind::Development => DependencyKind::Development,
+      cargo_metadata::DependencyKind::Build => DependencyKind::Build,
+      _ => DependencyKind::Normal,
+    }
+  }
+}
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Dependency {
   /// Package name.
   pub name: String,
+  /// Dependency kind.
+  pub kind: DependencyKind,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
@@ -23,19 +80,119 @@ pub struct Member {
   pub manifest_dir: Utf8PathBuf,
   /// Package path.
   pub path: String,
+  /// Flag indicating if the package may be published.
+  pub publish: bool,
+  /// Path to the readme file referenced in the manifest.
+  pub readme: Option<Utf8PathBuf>,
+  /// Path to the license file referenced in the manifest.
+  pub license_file: Option<Utf8PathBuf>,
+  /// Position of the dependency entry in `[workspace.dependencies]` table,
+  /// `usize::MAX` when the member is not listed there.
+  pub position: usize,
   /// Dependencies to other members.
   pub dependencies: Vec<Dependency>,
+  /// Hooks defined in `[package.metadata.univer]` table.
+  pub hooks: Hooks,
 }
 
 impl Member {
-  /// Returns the dependency prefix with version number.
-  pub fn dependency_with_version(&self, fixed_version: bool) -> String {
-    format!("{} = {{ version = \"{}{}\"", self.name, if fixed_version { "=" } else { "" }, self.version)
+  /// Returns the key-value pair with version number preceded by the requirement operator.
+  ///
+  /// Pre-releases are required exactly by default, plain requirement would match newer pre-releases.
+  pub fn dependency_with_version(&self, req_style: ReqStyle) -> String {
+    let req_style = if req_style == ReqStyle::Plain && self.is_pre_release() {
+      ReqStyle::Exact
+    } else {
+      req_style
+    };
+    format!("version = \"{}{}\"", req_style.operator(), self.version)
+  }
+
+  /// Returns `true` when the crate version is a pre-release.
+  pub fn is_pre_release(&self) -> bool {
+    bump::is_pre_release(&self.version)
   }
 
-  /// Returns the dependency prefix with local path.
+  /// Returns the key-value pair with local path.
   pub fn dependency_with_path(&self) -> String {
-    format!("{} = {{ path = \"{}\"", self.name, self.path)
+    format!("path = \"{}\"", self.path)
+  }
+
+  /// Returns the entry of the dependency (inline table) found in the workspace manifest content.
+  pub fn find_dependency<'a>(&self, content: &'a str) -> Option<&'a str> {
+    self.dependency_range(content).map(|range| &content[range])
+  }
+
+  /// Returns the position of the dependency entry in the manifest content.
+  ///
+  /// Only the `[workspace.dependencies]` table is searched, entries with the same name
+  /// in other tables (like `[patch.*]` or `[workspace.metadata]`) are ignored.
+  fn dependency_range(&self, content: &str) -> Option<Range<usize>> {
+    let start = DEPENDENCIES_HEADER.find(content)?.end();
+    let end = HEADER.find_at(content, start).map_or(content.len(), |next_header| next_header.start());
+    let entry = DEPENDENCY_ENTRY
+      .captures_iter(&content[start..end])
+      .find(|captures| captures[1].trim_matches('"') == self.name)?
+      .get(0)?;
+    Some(start + entry.start()..start + entry.end())
+  }
+
+  /// Returns the key-value pair with any version requirement found in the dependency entry,
+  /// together with the style of the requirement.
+  pub fn find_dependency_with_version(&self, content: &str) -> Option<(String, ReqStyle)> {
+    let entry = self.find_dependency(content)?;
+    let captures = VERSION_ATTRIBUTE.captures(entry)?;
+    Some((captures[1].to_string(), ReqStyle::new(&captures[2])?))
+  }
+
+  /// Returns the version (without requirement operator) found in the dependency entry.
+  pub fn dependency_version(&self, content: &str) -> Option<String> {
+    let entry = self.find_dependency(content)?;
+    let captures = VERSION_ATTRIBUTE.captures(entry)?;
+    Some(captures[3].to_string())
+  }
+
+  /// Returns the key-value pair with local path found in the dependency entry.
+  pub fn find_dependency_with_path(&self, content: &str) -> Option<String> {
+    let entry = self.find_dependency(content)?;
+    let captures = PATH_ATTRIBUTE.captures_iter(entry).find(|captures| captures[2] == self.path)?;
+    Some(captures[1].to_string())
+  }
+
+  /// Returns the manifest content with both local path and version requirement set in the dependency entry,
+  /// the version is synchronized with the member version (hybrid mode).
+  ///
+  /// The style of an existing requirement is kept, the specified style is used for a missing one.
+  pub fn sync_dependency(&self, content: &str, req_style: ReqStyle) -> Result<String> {
+    let path = self.find_dependency_with_path(content);
+    let version = self.find_dependency_with_version(content);
+    Ok(match (path, version) {
+      (Some(_), Some((version, req_style))) => self.replace_in_dependency(content, &version, &self.dependency_with_version(req_style)),
+      (Some(path), None) => self.replace_in_dependency(content, &path, &format!("{}, {}", path, self.dependency_with_version(req_style))),
+      (None, Some((version, req_style))) => self.replace_in_dependency(content, &version, &format!("{}, {}", self.dependency_with_path(), self.dependency_with_version(req_style))),
+      (None, None) => {
+        return Err(univer_error!(
+          "dependency '{}' not found or has an invalid format, expected: {} = {{ {}, {} }}",
+          self.name,
+          self.name,
+          self.dependency_with_path(),
+          self.dependency_with_version(req_style)
+        ));
+      }
+    })
+  }
+
+  /// Replaces the key-value pair in the dependency entry, all other keys are preserved verbatim and in order.
+  pub fn replace_in_dependency(&self, content: &str, from: &str, to: &str) -> String {
+    let Some(range) = self.dependency_range(content) else {
+      return content.to_string();
+    };
+    let entry = &content[range.clone()];
+    let replaced_entry = entry.replacen(from, to, 1);
+    debug!("manifest edit: {} -> {}", entry.trim(), replaced_entry.trim());
+    let mut replaced = content.to_string();
+    replaced.replace_range(range, &replaced_entry);
+    replaced
   }
 }
 
@@ -45,8 +202,14 @@ pub struct Workspace {
   version: String,
   /// Path to workspace manifest file.
   manifest_path: Utf8PathBuf,
+  /// Path to the target directory of the workspace.
+  target_dir: Utf8PathBuf,
+  /// Configuration defined in `[workspace.metadata.univer]` table.
+  config: Config,
   /// Workspace members (publishable).
   pub members: Vec<Member>,
+  /// All workspace packages, including not publishable and excluded ones (not validated).
+  pub packages: Vec<Member>,
 }
 
 impl Workspace {
@@ -58,16 +221,44 @@ impl Workspace {
     &self.manifest_path
   }
 
-  /// Loads workspace metadata.
-  pub fn load(manifest_dir: &Path) -> Result<Self> {
+  /// Returns the directory where univer stores its working files.
+  pub fn univer_dir(&self) -> Utf8PathBuf {
+    self.target_dir.join("univer")
+  }
+
+  /// Returns the directory containing workspace manifest file.
+  pub fn root(&self) -> &Utf8Path {
+    self.manifest_path.parent().unwrap_or(&self.manifest_path)
+  }
+
+  pub fn config(&self) -> &Config {
+    &self.config
+  }
+
+  /// Loads workspace metadata, options override the workspace configuration.
+  pub fn load(manifest_dir: &Path, options: &Options) -> Result<Self> {
     let manifest_path = manifest_dir.join(RUST_MANIFEST_NAME);
+    // Load workspace configuration.
+    let manifest_toml = utils::parse_toml(&manifest_path)?;
+    let univer_table = manifest_toml.get("workspace").and_then(|w| w.get("metadata")).and_then(|m| m.get("univer"));
+    let config = Config::load(univer_table, options)?;
     // Perform custom validations on workspace manifest.
-    let workspace_version = validate_workspace(&manifest_path)?;
+    let workspace_version = validate_workspace(&manifest_path, config.hybrid)?;
+    // Positions of entries in the [workspace.dependencies] table (the table preserves the order).
+    let positions = manifest_toml
+      .get("workspace")
+      .and_then(|w| w.get("dependencies"))
+      .and_then(|d| d.as_table())
+      .map(|table| table.keys().cloned().collect::<Vec<String>>())
+      .unwrap_or_default();
     // Load metadata.
+    debug!("loading metadata of workspace {}", manifest_path.display());
+    // Dependencies are not resolved, so the lock file is left untouched until it is verified or updated.
     let mut metadata_command = MetadataCommand::new();
-    metadata_command.manifest_path(manifest_path);
+    metadata_command.manifest_path(&manifest_path).no_deps();
     let metadata = metadata_command.exec().map_err(|e| UniverError::new(format!("{}", e)))?;
     let mut members = vec![];
+    let mut packages = vec![];
     let workspace_root = &metadata.workspace_root;
     let member_names = metadata.workspace_packages().iter().map(|p| p.name.to_string()).collect::<Vec<String>>();
     for package in metadata.workspace_packages() {
@@ -77,36 +268,59 @@ impl Workspace {
       };
       let package_path = package_manifest_dir.strip_prefix(workspace_root).map_err(|e| UniverError::new(format!("{}", e)))?;
       let package_publish = package.publish.as_ref().map(|v| !v.is_empty()).unwrap_or(true);
-      if package_publish {
-        let mut dependencies = vec![];
-        for dependency in &package.dependencies {
-          if member_names.contains(&dependency.name) {
-            dependencies.push(Dependency { name: dependency.name.clone() })
+      let mut dependencies = vec![];
+      for dependency in &package.dependencies {
+        if member_names.contains(&dependency.name) {
+          let dependency = Dependency {
+            name: dependency.name.clone(),
+            kind: dependency.kind.into(),
+          };
+          if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
           }
         }
-        members.push(Member {
-          name: package.name.to_string(),
-          version: package.version.to_string(),
-          manifest_path: package_manifest_path.into(),
-          manifest_dir: package_manifest_dir.into(),
-          path: package_path.to_string().replace("\\", "/"),
-          dependencies,
-        });
       }
+      let member = Member {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        manifest_path: package_manifest_path.into(),
+        manifest_dir: package_manifest_dir.into(),
+        path: package_path.to_string().replace("\\", "/"),
+        publish: package_publish,
+        readme: package.readme(),
+        license_file: package.license_file(),
+        position: positions.iter().position(|name| name == package.name.as_str()).unwrap_or(usize::MAX),
+        dependencies,
+        hooks: Hooks::default(),
+      };
+      if package_publish && !config.is_excluded(&package.name) {
+        debug!("member {} v{} at {}", member.name, member.version, member.path);
+        members.push(member.clone());
+      } else {
+        debug!("package {} v{} at {} skipped, not published or excluded", member.name, member.version, member.path);
+      }
+      packages.push(member);
     }
-    for member in &members {
+    let manifest_content = utils::read_file(&manifest_path)?;
+    for member in &mut members {
       // Perform custom validations on each member.
-      validate_member(member)?;
+      validate_member(member, config.independent_versions)?;
+      validate_dependency_entry(member, &manifest_content)?;
+      // Load member hooks.
+      member.hooks = load_member_hooks(member)?;
     }
     Ok(Self {
       version: workspace_version,
       manifest_path: workspace_root.join(RUST_MANIFEST_NAME),
+      target_dir: metadata.target_directory.clone(),
+      config,
       members,
+      packages,
     })
   }
 }
 
-fn validate_workspace(manifest_path: &Path) -> Result<String> {
+fn validate_workspace(manifest_path: &Path, hybrid: bool) -> Result<String> {
   let manifest_toml = utils::parse_toml(manifest_path)?;
   // Check if the manifest file is a workspace (required).
   let Some(workspace) = manifest_toml.get("workspace") else {
@@ -134,7 +348,7 @@ fn validate_workspace(manifest_path: &Path) -> Result<String> {
   let Some(dependencies_table) = dependencies_table.as_table() else {
     return Err(univer_error!("[workspace.dependencies] is not a table"));
   };
-  // Check if no both 'path' and 'version' are set for dependency.
+  // Check if no both 'path' and 'version' are set for dependency, unless in hybrid mode.
   for (name, value) in dependencies_table {
     let mut opt_path = None;
     if let Some(path) = value.get("path") {
@@ -150,14 +364,15 @@ fn validate_workspace(manifest_path: &Path) -> Result<String> {
       };
       opt_version = Some(version);
     }
-    if opt_path.is_some() && opt_version.is_some() {
+    if !hybrid && opt_path.is_some() && opt_version.is_some() {
       return Err(univer_error!("dependency '{}' has 'path' and 'version' set in [workspace.dependencies] table", name));
     }
   }
   Ok(version)
 }
 
-fn validate_member(member: &Member) -> Result<()> {
+/// Validates the member manifest, own version is allowed in independent versions mode.
+fn validate_member(member: &Member, independent_versions: bool) -> Result<()> {
   let manifest_toml = utils::parse_toml(&member.manifest_path)?;
   let Some(package) = manifest_toml.get("package") else {
     return Err(univer_error!("missing [package] section in manifest for dependency '{}'", member.name));
@@ -165,14 +380,17 @@ fn validate_member(member: &Member) -> Result<()> {
   let Some(package_version) = package.get("version") else {
     return Err(univer_error!("missing [package].version attribute in manifest for dependency '{}'", member.name));
   };
-  let Some(package_version_workspace) = package_version.get("workspace") else {
-    return Err(univer_error!("missing [package].version.workspace attribute in manifest for dependency '{}'", member.name));
-  };
-  let Some(package_version_workspace_value) = package_version_workspace.as_bool() else {
-    return Err(univer_error!("invalid [package].version.workspace attribute in manifest for dependency '{}'", member.name));
-  };
-  if !package_version_workspace_value {
-    return Err(univer_error!("[package].version.workspace attribute in crate '{}' must have value 'true'", member.name));
+  // In independent versions mode, the member may declare its own version.
+  if !(independent_versions && package_version.is_str()) {
+    let Some(package_version_workspace) = package_version.get("workspace") else {
+      return Err(univer_error!("missing [package].version.workspace attribute in manifest for dependency '{}'", member.name));
+    };
+    let Some(package_version_workspace_value) = package_version_workspace.as_bool() else {
+      return Err(univer_error!("invalid [package].version.workspace attribute in manifest for dependency '{}'", member.name));
+    };
+    if !package_version_workspace_value {
+      return Err(univer_error!("[package].version.workspace attribute in crate '{}' must have value 'true'", member.name));
+    }
   }
   if let Some(dependencies) = manifest_toml.get("dependencies") {
     let Some(dependencies_table) = dependencies.as_table() else {
@@ -189,6 +407,28 @@ fn validate_member(member: &Member) -> Result<()> {
   Ok(())
 }
 
+/// Validates the shape of the member entry in `[workspace.dependencies]` table.
+///
+/// Entries are edited as text, so only inline tables on a single line are supported,
+/// other shapes (like dotted keys or separate tables) are reported instead of being skipped.
+fn validate_dependency_entry(member: &Member, content: &str) -> Result<()> {
+  if member.position != usize::MAX && member.find_dependency(content).is_none() {
+    return Err(univer_error!(
+      "unsupported format of dependency '{}' in [workspace.dependencies] table, expected an inline table on a single line: {} = {{ {} }}",
+      member.name,
+      member.name,
+      member.dependency_with_path()
+    ));
+  }
+  Ok(())
+}
+
+fn load_member_hooks(member: &Member) -> Result<Hooks> {
+  let manifest_toml = utils::parse_toml(&member.manifest_path)?;
+  let univer_table = manifest_toml.get("package").and_then(|p| p.get("metadata")).and_then(|m| m.get("univer"));
+  Hooks::load(univer_table, &format!("[package.metadata.univer] table in crate '{}'", member.name))
+}
+
 fn validate_crate_dependencies(dependencies: &toml::Table, member: &Member) -> Result<()> {
   // Iterate over all dependencies defined in the table.
   for (key, value) in dependencies {
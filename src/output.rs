@@ -0,0 +1,205 @@
+//! # Output of messages with verbosity levels and colors
+
+use antex::Text;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Verbosity of the output, set once from the command line.
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Color choice of the output, set once from the command line.
+static COLOR: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Amount of reported details.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Verbosity {
+  /// Only warnings, errors, questions and requested results are reported.
+  Quiet,
+  /// Progress and plans of actions are reported.
+  #[default]
+  Normal,
+  /// Details, like the effective configuration and debug traces, are reported too.
+  Verbose,
+}
+
+/// When the output is colored.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ColorChoice {
+  /// Colored when printed to a terminal.
+  #[default]
+  Auto,
+  /// Always colored.
+  Always,
+  /// Never colored.
+  Never,
+}
+
+impl ColorChoice {
+  /// Creates the color choice from its name.
+  pub fn new(name: &str) -> Option<Self> {
+    match name {
+      "auto" => Some(ColorChoice::Auto),
+      "always" => Some(ColorChoice::Always),
+      "never" => Some(ColorChoice::Never),
+      _ => None,
+    }
+  }
+
+  /// Resolves the color choice from the command line and the environment.
+  ///
+  /// The choice passed from the command line takes precedence, then `NO_COLOR` (when not empty)
+  /// disables colors and `CARGO_TERM_COLOR` (like in Cargo) is respected, otherwise the output
+  /// is colored only when printed to a terminal.
+  pub fn resolve(color: Option<ColorChoice>) -> Self {
+    Self::resolve_with(color, std::env::var_os("NO_COLOR"), std::env::var("CARGO_TERM_COLOR").ok())
+  }
+
+  /// Resolves the color choice from the command line and specified values of `NO_COLOR` and `CARGO_TERM_COLOR`.
+  fn resolve_with(color: Option<ColorChoice>, no_color: Option<OsString>, cargo_term_color: Option<String>) -> Self {
+    color
+      .or_else(|| no_color.filter(|value| !value.is_empty()).map(|_| ColorChoice::Never))
+      .or_else(|| cargo_term_color.and_then(|value| ColorChoice::new(value.trim())))
+      .unwrap_or_default()
+  }
+
+  /// Returns `true` when the stream (being a terminal or not) is colored.
+  fn is_colored(&self, is_terminal: bool) -> bool {
+    match self {
+      ColorChoice::Auto => is_terminal,
+      ColorChoice::Always => true,
+      ColorChoice::Never => false,
+    }
+  }
+}
+
+impl std::fmt::Display for ColorChoice {
+  /// Implementation of [Display](std::fmt::Display) trait for [ColorChoice].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ColorChoice::Auto => write!(f, "auto"),
+      ColorChoice::Always => write!(f, "always"),
+      ColorChoice::Never => write!(f, "never"),
+    }
+  }
+}
+
+/// Sets the verbosity of the output and resolves the color choice.
+pub fn init(verbosity: Verbosity, color: Option<ColorChoice>) {
+  VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+  COLOR.store(ColorChoice::resolve(color) as u8, Ordering::Relaxed);
+}
+
+/// Returns the verbosity of the output.
+pub fn verbosity() -> Verbosity {
+  match VERBOSITY.load(Ordering::Relaxed) {
+    0 => Verbosity::Quiet,
+    2 => Verbosity::Verbose,
+    _ => Verbosity::Normal,
+  }
+}
+
+/// Returns `true` when only warnings, errors, questions and requested results are reported.
+pub fn is_quiet() -> bool {
+  verbosity() == Verbosity::Quiet
+}
+
+/// Returns `true` when details and debug traces are reported.
+pub fn is_verbose() -> bool {
+  verbosity() == Verbosity::Verbose
+}
+
+/// Returns the color choice of the output.
+pub fn color() -> ColorChoice {
+  match COLOR.load(Ordering::Relaxed) {
+    1 => ColorChoice::Always,
+    2 => ColorChoice::Never,
+    _ => ColorChoice::Auto,
+  }
+}
+
+/// Returns the text styled for the standard output, according to the color choice.
+pub fn auto() -> Text {
+  styled(color().is_colored(std::io::stdout().is_terminal()))
+}
+
+/// Returns the text styled for the standard error, according to the color choice.
+pub fn auto_stderr() -> Text {
+  styled(color().is_colored(std::io::stderr().is_terminal()))
+}
+
+/// Returns the value of `--color` argument passed to cargo, cargo reports to the standard error.
+pub fn cargo_color() -> &'static str {
+  if color().is_colored(std::io::stderr().is_terminal()) { "always" } else { "never" }
+}
+
+/// Returns the colored or plain text.
+fn styled(colored: bool) -> Text {
+  if colored { antex::always() } else { antex::never() }
+}
+
+/// Prints the message, unless the output is quiet.
+macro_rules! info {
+  ($($arg:tt)*) => {{
+    if !crate::output::is_quiet() {
+      println!($($arg)*);
+    }
+  }};
+}
+
+/// Prints the debug trace to the standard error, when the output is verbose.
+macro_rules! debug {
+  ($($arg:tt)*) => {{
+    if crate::output::is_verbose() {
+      use antex::StyledText;
+      eprintln!("{}{}", crate::output::auto_stderr().cyan().s("debug").reset().s(": "), format!($($arg)*));
+    }
+  }};
+}
+
+/// Prints the warning to the standard error, also when the output is quiet.
+macro_rules! warning {
+  ($($arg:tt)*) => {{
+    use antex::StyledText;
+    eprintln!("{}", crate::output::auto_stderr().yellow().s("warning: ").s(format!($($arg)*)).reset());
+  }};
+}
+
+pub(crate) use {debug, info, warning};
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn resolve(color: Option<ColorChoice>, no_color: Option<&str>, cargo_term_color: Option<&str>) -> ColorChoice {
+    ColorChoice::resolve_with(color, no_color.map(OsString::from), cargo_term_color.map(String::from))
+  }
+
+  #[test]
+  fn test_resolve_default() {
+    assert_eq!(ColorChoice::Auto, resolve(None, None, None));
+  }
+
+  #[test]
+  fn test_resolve_command_line() {
+    assert_eq!(ColorChoice::Always, resolve(Some(ColorChoice::Always), Some("1"), Some("never")));
+    assert_eq!(ColorChoice::Never, resolve(Some(ColorChoice::Never), None, Some("always")));
+    assert_eq!(ColorChoice::Auto, resolve(Some(ColorChoice::Auto), Some("1"), Some("always")));
+  }
+
+  #[test]
+  fn test_resolve_no_color() {
+    assert_eq!(ColorChoice::Never, resolve(None, Some("1"), None));
+    assert_eq!(ColorChoice::Never, resolve(None, Some("1"), Some("always")));
+    assert_eq!(ColorChoice::Always, resolve(None, Some(""), Some("always")));
+    assert_eq!(ColorChoice::Auto, resolve(None, Some(""), None));
+  }
+
+  #[test]
+  fn test_resolve_cargo_term_color() {
+    assert_eq!(ColorChoice::Always, resolve(None, None, Some("always")));
+    assert_eq!(ColorChoice::Never, resolve(None, None, Some(" never ")));
+    assert_eq!(ColorChoice::Auto, resolve(None, None, Some("auto")));
+    assert_eq!(ColorChoice::Auto, resolve(None, None, Some("sometimes")));
+  }
+}
@@ -0,0 +1,147 @@
+//! # Management of owners of all workspace crates
+
+use crate::config::Options;
+use crate::errors::{Result, univer_error};
+use crate::model::{Member, Workspace};
+use crate::output::{self, auto, info};
+use crate::utils;
+use antex::StyledText;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Operation performed on owners of workspace crates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnersCommand {
+  /// List owners of all crates.
+  List,
+  /// Add the owner with specified login to all crates.
+  Add(String),
+  /// Remove the owner with specified login from all crates.
+  Remove(String),
+}
+
+/// Lists, adds or removes owners of all publishable workspace crates.
+pub fn owners(manifest_dir: &Path, command: &OwnersCommand, dry_run: bool, accept_all: bool, options: &Options) -> Result<()> {
+  let workspace = Workspace::load(manifest_dir, options)?;
+  let config = workspace.config();
+  if workspace.members.is_empty() {
+    return Err(univer_error!("no crates found"));
+  }
+  let members = utils::sort(workspace.members.clone(), config.sort)?;
+  let registry = config.registry.as_deref();
+  let (login, add) = match command {
+    OwnersCommand::List => return list(&workspace, &members, registry),
+    OwnersCommand::Add(login) => (login, true),
+    OwnersCommand::Remove(login) => (login, false),
+  };
+  info!();
+  info!("{} owner: {}", if add { "Add" } else { "Remove" }, auto().bold().green().s(login).reset());
+  info!();
+  info!("Crates:");
+  for member in &members {
+    info!("{}  {}", auto().bold().blue().s(&member.name).reset(), member.path);
+  }
+  info!();
+  let question = if add {
+    format!("Do you want to add owner '{}' to all these crates?", login)
+  } else {
+    format!("Do you want to remove owner '{}' from all these crates?", login)
+  };
+  if dry_run || !utils::prompt(&question, accept_all)? {
+    return Ok(());
+  }
+  for member in &members {
+    let color = format!("--color={}", output::cargo_color());
+    let mut args = vec!["owner", if add { "--add" } else { "--remove" }, login, &color];
+    if let Some(registry) = registry {
+      args.extend(["--registry", registry]);
+    }
+    args.push(&member.name);
+    utils::execute_command("cargo", args, workspace.root(), &[]).map_err(|e| univer_error!("changing owners of crate '{}' failed, reason: {}", member.name, e))?;
+  }
+  Ok(())
+}
+
+/// Prints the matrix of crates and their owners, owners missing in some crates are highlighted.
+fn list(workspace: &Workspace, members: &[Member], registry: Option<&str>) -> Result<()> {
+  let mut crate_owners = vec![];
+  for member in members {
+    crate_owners.push((member, list_owners(workspace, member, registry)?));
+  }
+  let all_owners = crate_owners.iter().flat_map(|(_, owners)| owners.iter().cloned()).collect::<BTreeSet<String>>();
+  let name_width = members.iter().map(|member| member.name.len()).max().unwrap_or_default();
+  println!();
+  print!("{:<1$}", "", name_width);
+  for owner in &all_owners {
+    let consistent = crate_owners.iter().all(|(_, owners)| owners.contains(owner));
+    if consistent {
+      print!("  {}", auto().bold().s(owner).reset());
+    } else {
+      print!("  {}", auto().bold().yellow().s(owner).reset());
+    }
+  }
+  println!();
+  for (member, owners) in &crate_owners {
+    print!("{}{}", auto().bold().blue().s(&member.name).reset(), " ".repeat(name_width - member.name.len()));
+    // Cells are aligned with owner logins, without trailing spaces.
+    let mut padding = 0;
+    for owner in &all_owners {
+      if owners.contains(owner) {
+        print!("{}  {}", " ".repeat(padding), auto().green().s('x').reset());
+      } else {
+        print!("{}  {}", " ".repeat(padding), auto().red().s('-').reset());
+      }
+      padding = owner.len() - 1;
+    }
+    println!();
+  }
+  // Report owners that do not own all crates.
+  let inconsistent = all_owners
+    .iter()
+    .filter_map(|owner| {
+      let missing = crate_owners
+        .iter()
+        .filter(|(_, owners)| !owners.contains(owner))
+        .map(|(member, _)| member.name.as_str())
+        .collect::<Vec<&str>>();
+      (!missing.is_empty()).then_some((owner, missing))
+    })
+    .collect::<Vec<_>>();
+  if !inconsistent.is_empty() {
+    println!();
+    println!("Inconsistent owners:");
+    for (owner, missing) in inconsistent {
+      println!("{}  missing in: {}", auto().bold().yellow().s(owner).reset(), missing.join(", "));
+    }
+  }
+  Ok(())
+}
+
+/// Returns logins of owners of the crate, listed using `cargo owner --list`.
+fn list_owners(workspace: &Workspace, member: &Member, registry: Option<&str>) -> Result<BTreeSet<String>> {
+  let mut command = std::process::Command::new("cargo");
+  command.args(["owner", "--list"]);
+  if let Some(registry) = registry {
+    command.args(["--registry", registry]);
+  }
+  let output = command
+    .arg(&member.name)
+    .current_dir(workspace.root())
+    .output()
+    .map_err(|e| univer_error!("listing owners of crate '{}' failed, reason: {}", member.name, e))?;
+  if !output.status.success() {
+    return Err(univer_error!(
+      "listing owners of crate '{}' failed, reason: {}",
+      member.name,
+      String::from_utf8_lossy(&output.stderr).trim()
+    ));
+  }
+  // Each line contains the login, optionally followed by the name in parentheses.
+  Ok(
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| line.split_whitespace().next())
+      .map(|login| login.to_string())
+      .collect(),
+  )
+}
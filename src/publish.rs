@@ -1,108 +1,479 @@
+use crate::config::{Config, Integration, Options, ReqStyle};
 use crate::errors::*;
-use crate::model::Workspace;
-use crate::utils;
-use antex::{StyledText, auto};
-use std::ffi::OsStr;
+use crate::hooks::HookKind;
+use crate::journal::Journal;
+use crate::model::{Member, Workspace};
+use crate::output::{auto, info, warning};
+use crate::replacements::{FileChange, Placeholders};
+use crate::summary::Summary;
+use crate::{backup, changed, hooks, integration, lock, output, replacements, retry, semver_check, utils, verify};
+use antex::{StyledText, Text};
+use cargo_metadata::camino::Utf8PathBuf;
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
 
-pub fn publish(manifest_dir: &Path, dry_run: bool, accept_all: bool, fixed_version: bool) -> Result<()> {
-  let workspace = Workspace::load(manifest_dir)?;
+pub fn publish(manifest_dir: &Path, dry_run: bool, accept_all: bool, options: &Options) -> Result<()> {
+  let workspace = Workspace::load(manifest_dir, options)?;
+  let config = workspace.config();
+  if output::is_verbose() {
+    config.report();
+  }
+  // The lock file must be up to date before anything is changed.
+  if config.locked {
+    lock::verify(&workspace)?;
+  }
   let mut manifest_content = utils::read_file(workspace.manifest_path())?;
+  let mut journal = Journal::load(&workspace)?;
+  // In hybrid mode versions of dependencies are expected to match crate versions, mismatches are reported before synchronizing.
+  let mismatches = if config.hybrid {
+    workspace
+      .members
+      .iter()
+      .filter_map(|member| {
+        member
+          .dependency_version(&manifest_content)
+          .filter(|version| *version != member.version)
+          .map(|version| (member, version))
+      })
+      .collect::<Vec<_>>()
+  } else {
+    vec![]
+  };
   // Select members with path to be published.
   let mut members_to_publish = vec![];
   for member in &workspace.members {
-    let dependency_with_path = &member.dependency_with_path();
-    let dependency_with_version = &member.dependency_with_version(fixed_version);
-    if manifest_content.contains(dependency_with_path) {
+    if config.hybrid {
+      // In hybrid mode all crates are published, versions of dependencies are synchronized with crate versions.
+      manifest_content = member.sync_dependency(&manifest_content, config.req_style(&member.name))?;
+      members_to_publish.push(member.clone());
+    } else if member.find_dependency_with_path(&manifest_content).is_some() {
       members_to_publish.push(member.clone());
-    } else if !manifest_content.contains(dependency_with_version) {
+    } else if member.find_dependency_with_version(&manifest_content).is_none() {
       return Err(univer_error!(
-        "dependency '{}' with path '{}' not found or has an invalid format, expected: {}",
+        "dependency '{}' with path '{}' not found or has an invalid format, expected: {} = {{ {} }}",
         member.name,
         member.path,
-        dependency_with_path
+        member.name,
+        member.dependency_with_path()
       ));
     }
   }
+  // Only crates changed since the previous release are published, when requested.
+  let mut unchanged_members = vec![];
+  if options.changed {
+    if !config.independent_versions {
+      return Err(univer_error!("publishing only changed crates requires independent versions"));
+    }
+    let changes = changed::detect(&workspace, options.since.as_deref())?;
+    (members_to_publish, unchanged_members) = members_to_publish.into_iter().partition(|member| changes.contains_key(&member.name));
+  }
   // Check if there are any crates to publish.
   if members_to_publish.is_empty() {
     return Err(univer_error!("no crates to publish"));
   }
   // Sort crates in the order of publishing.
-  let members_to_publish = utils::sort(members_to_publish);
-  // Ask if the version to be published is correct.
-  println!();
-  println!("Publish version: {}", auto().bold().green().s(workspace.version()).reset());
-  if !dry_run && !utils::prompt("Is this version correct?", accept_all)? {
-    return Ok(());
+  let members_to_publish = utils::sort(members_to_publish, config.sort)?;
+  let levels = utils::levels(&members_to_publish);
+  // Crates are grouped by topological level when published concurrently.
+  let groups = if config.jobs > 1 {
+    let mut groups: Vec<Vec<Member>> = vec![];
+    for member in &members_to_publish {
+      let level = levels[&member.name];
+      if groups.len() <= level {
+        groups.resize(level + 1, vec![]);
+      }
+      groups[level].push(member.clone());
+    }
+    groups
+  } else {
+    members_to_publish.iter().map(|member| vec![member.clone()]).collect()
+  };
+  // Load pre-release replacements for all crates to be published and check them,
+  // so invalid replacements are reported before anything is published.
+  let mut members_replacements = HashMap::new();
+  for member in &members_to_publish {
+    let member_replacements = replacements::load(member)?;
+    if !member_replacements.is_empty() {
+      replacements::prepare(member, &member_replacements, &placeholders(member, config))?;
+    }
+    members_replacements.insert(member.name.clone(), member_replacements);
+  }
+  // Verify contents of packaged crates.
+  let mut verifications = HashMap::new();
+  if config.verify {
+    for member in &members_to_publish {
+      verifications.insert(member.name.clone(), verify::verify(member, config)?);
+    }
+  }
+  // Ask if the version to be published is correct, crates with independent versions are listed with their own versions.
+  if !config.independent_versions {
+    info!();
+    info!("Publish version: {}", auto().bold().green().s(workspace.version()).reset());
+    if !dry_run && !utils::prompt("Is this version correct?", accept_all)? {
+      return Ok(());
+    }
   }
   // List all the crates to be published with versions and ask if the list is correct.
-  println!();
-  println!("Publish crates:");
+  info!();
+  info!("Publish crates:");
   for member in &members_to_publish {
-    println!(
-      "{}  {}  {}",
+    let verification = verifications.get(&member.name);
+    info!(
+      "{}  {}  {}  {}{}",
       auto().bold().blue().s(&member.name).reset(),
-      auto().bold().green().s('v').s(workspace.version()).reset(),
-      member.path
+      auto().bold().green().s('v').s(&member.version).reset(),
+      member.path,
+      auto().s("(level ").s(levels[&member.name]).s(')').reset(),
+      verification
+        .filter(|verification| verification.files > 0)
+        .map(|verification| format!("  {}", verification.summary()))
+        .unwrap_or_default()
     );
+    for problem in verification.iter().flat_map(|verification| &verification.problems) {
+      info!("  {}", auto().red().s(problem).reset());
+    }
+  }
+  if !unchanged_members.is_empty() {
+    info!();
+    info!("Unchanged crates (not published):");
+    for member in &unchanged_members {
+      info!(
+        "{}  {}  {}",
+        auto().bold().blue().s(&member.name).reset(),
+        auto().bold().green().s('v').s(&member.version).reset(),
+        member.path
+      );
+    }
+  }
+  if !mismatches.is_empty() {
+    info!();
+    info!("Versions of dependencies differ from versions of crates:");
+    for (member, version) in &mismatches {
+      info!(
+        "{}  {} -> {}",
+        auto().bold().blue().s(&member.name).reset(),
+        auto().bold().red().s(version).reset(),
+        auto().bold().green().s(&member.version).reset()
+      );
+    }
+    if !dry_run && !utils::prompt("Synchronize versions of these dependencies?", accept_all)? {
+      let mut names = mismatches.iter().map(|(member, _)| member.name.as_str()).collect::<Vec<&str>>();
+      names.sort();
+      return Err(univer_error!("versions of dependencies do not match versions of crates: {}", names.join(", ")));
+    }
+  }
+  // Warn about pre-releases required with operators matching newer pre-releases.
+  for member in members_to_publish.iter().filter(|member| member.is_pre_release()) {
+    let req_style = if config.hybrid {
+      member
+        .find_dependency_with_version(&manifest_content)
+        .map(|(_, req_style)| req_style)
+        .unwrap_or_else(|| config.req_style(&member.name))
+    } else {
+      req_style(member, &journal, config, options)
+    };
+    if matches!(req_style, ReqStyle::Caret | ReqStyle::Tilde) {
+      warning!(
+        "dependents of '{}' may resolve newer pre-releases through '{}{}', consider '=' requirement",
+        member.name,
+        req_style.operator(),
+        member.version
+      );
+    }
+  }
+  info!();
+  let mut failed = members_to_publish
+    .iter()
+    .filter(|member| verifications.get(&member.name).is_some_and(|verification| !verification.problems.is_empty()))
+    .map(|member| member.name.as_str())
+    .collect::<Vec<&str>>();
+  if !failed.is_empty() {
+    failed.sort();
+    return Err(univer_error!("verification failed for crates: {}", failed.join(", ")));
+  }
+  // Build (or test) packaged crates together, before anything is published.
+  if config.integration != Integration::None {
+    let failed = integration::check(&workspace, &members_to_publish, &manifest_content)?;
+    if !failed.is_empty() {
+      return Err(univer_error!("integration failed for crates: {}", failed.join(", ")));
+    }
+    info!();
+  }
+  // Check that the planned version bump matches changes in the public API.
+  if config.semver_check {
+    let failed = semver_check::check(&workspace, &members_to_publish, options.since.as_deref())?;
+    if !failed.is_empty() {
+      return Err(univer_error!("semver check failed for crates: {}", failed.join(", ")));
+    }
+    info!();
   }
-  println!();
   if !dry_run && !utils::prompt("Do you want to publish all these crates?", accept_all)? {
     return Ok(());
   }
-
-  // Publish crates.
-  for member in &members_to_publish {
+  // Locked packages are remembered to report changes of the lock file updated after publishing.
+  // The lock file is not updated in dry run, dependencies on unpublished crates can not be resolved.
+  let locked_packages = if config.update_lock && !dry_run { Some(lock::packages(&workspace)?) } else { None };
+  // Back up the workspace manifest before it is modified.
+  backup::create(&workspace.univer_dir(), workspace.manifest_path(), "publish")?;
+  if config.hybrid {
+    utils::write_file(workspace.manifest_path(), &manifest_content)?;
+  } else if !unchanged_members.is_empty() {
+    // Unchanged crates are already released, dependencies refer to their versions.
+    for member in &unchanged_members {
+      if let Some(dependency_with_path) = member.find_dependency_with_path(&manifest_content) {
+        let dependency_with_version = member.dependency_with_version(req_style(member, &journal, config, options));
+        manifest_content = member.replace_in_dependency(&manifest_content, &dependency_with_path, &dependency_with_version);
+      }
+      journal.remove_requirement(&member.name);
+    }
+    utils::write_file(workspace.manifest_path(), &manifest_content)?;
+    // Remembered requirement styles are needed by the real publishing, after the dry run.
+    if !dry_run {
+      journal.save()?;
+    }
+  }
+  // Execute workspace hook before publishing.
+  let workspace_envs = hooks::workspace_envs(&workspace, &members_to_publish, dry_run);
+  hooks::run(&config.hooks, HookKind::PrePublish, "workspace", workspace.root().as_ref(), &workspace_envs, dry_run)?;
+  // Publish crates, group by group, outcomes of cargo commands are summarized.
+  let mut summary = Summary::new(&workspace, &members_to_publish)?;
+  // Files rewritten by pre-release replacements are not committed, crates containing them are published with `--allow-dirty`.
+  let mut rewritten: Vec<Utf8PathBuf> = vec![];
+  for (level, group) in groups.iter().enumerate() {
+    let single = group.len() == 1;
+    if config.jobs > 1 {
+      info!(
+        "\n{} {}",
+        auto().bold().s("Level ").s(level).s(':').reset(),
+        group.iter().map(|member| member.name.as_str()).collect::<Vec<&str>>().join(", ")
+      );
+    }
+    for member in group {
+      // Ask if apply pre-release replacements.
+      let member_replacements = &members_replacements[&member.name];
+      if !member_replacements.is_empty() {
+        print_header(auto().bold().bg_magenta().s("  REPLACE  ").reset(), member);
+        // Prepared again, files could have been changed by replacements of previous crates.
+        let changes = replacements::prepare(member, member_replacements, &placeholders(member, config))?;
+        print_preview(&changes);
+        if !dry_run && utils::prompt("Apply these replacements?", accept_all)? {
+          replacements::apply(&changes)?;
+          rewritten.extend(
+            changes
+              .iter()
+              .filter(|change| change.content != change.original)
+              .filter_map(|change| change.path.canonicalize_utf8().ok()),
+          );
+        }
+      }
+      // Execute crate hook before publishing.
+      let member_envs = hooks::member_envs(&workspace, member, dry_run);
+      hooks::run(&member.hooks, HookKind::PrePublish, &member.name, member.manifest_dir.as_ref(), &member_envs, dry_run)?;
+    }
     // Ask if perform dry-run before publishing.
-    println!(
-      "\n{} {} {} {}",
-      auto().bold().bg_yellow().s("  DRY-RUN  ").reset(),
-      auto().bold().blue().s(&member.name).reset(),
-      auto().bold().green().s('v').s(workspace.version()).reset(),
-      member.path
-    );
-    if !dry_run && utils::prompt("Perform dry-run before publishing this crate?", accept_all)? {
-      execute_command("cargo", ["publish", "--dry-run", "--color=always"], &member.manifest_dir)?;
+    for member in group {
+      print_header(auto().bold().bg_yellow().s("  DRY-RUN  ").reset(), member);
     }
-    // Ask if publish the crate.
-    println!(
-      "\n{} {} {} {}",
-      auto().bold().bg_red().s("  PUBLISH  ").reset(),
-      auto().bold().blue().s(&member.name).reset(),
-      auto().bold().green().s('v').s(workspace.version()).reset(),
-      member.path
-    );
-    if !dry_run && utils::prompt("Publish this crate?", accept_all)? {
-      execute_command("cargo", ["publish", "--color=always"], &member.manifest_dir)?;
+    let question = if single {
+      "Perform dry-run before publishing this crate?"
+    } else {
+      "Perform dry-run before publishing these crates?"
+    };
+    if !dry_run && utils::prompt(question, accept_all)? {
+      execute_publish(&workspace, group, true, &rewritten, &mut summary).inspect_err(|_| summary.print())?;
+    }
+    // Ask if publish the crates.
+    for member in group {
+      print_header(auto().bold().bg_red().s("  PUBLISH  ").reset(), member);
+    }
+    let question = if single { "Publish this crate?" } else { "Publish these crates?" };
+    let published = !dry_run && utils::prompt(question, accept_all)?;
+    if published {
+      execute_publish(&workspace, group, false, &rewritten, &mut summary).inspect_err(|_| summary.print())?;
+    }
+    // Update the workspace manifest, one crate at a time (paths are kept in hybrid mode).
+    for member in group {
+      if !config.hybrid {
+        let req_style = req_style(member, &journal, config, options);
+        if let Some(dependency_with_path) = member.find_dependency_with_path(&manifest_content) {
+          manifest_content = member.replace_in_dependency(&manifest_content, &dependency_with_path, &member.dependency_with_version(req_style));
+        }
+        utils::write_file(workspace.manifest_path(), &manifest_content)?;
+        if !dry_run {
+          journal.remove_requirement(&member.name);
+          journal.save()?;
+        }
+      }
+    }
+    // Update the lock file after the manifest is changed, published crates can be resolved now.
+    if published && locked_packages.is_some() {
+      lock::refresh(&workspace)?;
+    }
+    // Execute crate hooks after publishing, skipped when publishing was declined.
+    if published || dry_run {
+      for member in group {
+        let member_envs = hooks::member_envs(&workspace, member, dry_run);
+        hooks::run(&member.hooks, HookKind::PostPublish, &member.name, member.manifest_dir.as_ref(), &member_envs, dry_run)?;
+      }
     }
-    let dependency_with_path = &member.dependency_with_path();
-    let dependency_with_version = &member.dependency_with_version(fixed_version);
-    manifest_content = manifest_content.replace(dependency_with_path, dependency_with_version);
-    utils::write_file(workspace.manifest_path(), &manifest_content)?;
   }
-
+  // Report changes of the lock file, updated after each published group.
+  if let Some(locked_packages) = locked_packages {
+    lock::report(&lock::diff(&locked_packages, &lock::packages(&workspace)?));
+  }
+  // Execute workspace hook after publishing.
+  hooks::run(&config.hooks, HookKind::PostPublish, "workspace", workspace.root().as_ref(), &workspace_envs, dry_run)?;
+  summary.print();
   Ok(())
 }
 
-fn execute_command<S, A, P>(program: S, args: A, dir: P) -> Result<()>
-where
-  S: AsRef<OsStr>,
-  A: IntoIterator<Item = S>,
-  P: AsRef<Path>,
-{
-  let mut command = std::process::Command::new(program);
-  let mut child = command
-    .args(args)
-    .current_dir(dir)
-    .stdin(std::process::Stdio::inherit())
-    .stdout(std::process::Stdio::inherit())
-    .stderr(std::process::Stdio::inherit())
-    .spawn()
-    .map_err(|e| univer_error!("{}", e))?;
-  let exit_status = child.wait().map_err(|e| univer_error!("{}", e))?;
-  if !exit_status.success() {
-    return Err(univer_error!("executing command failed with status code: {}", exit_status));
+/// Returns the requirement style used when the dependency is switched from path to version.
+///
+/// Requirement style remembered when switching to development mode is restored,
+/// unless requirement styles are set from the command line.
+fn req_style(member: &Member, journal: &Journal, config: &Config, options: &Options) -> ReqStyle {
+  journal
+    .requirement(&member.name)
+    .filter(|_| !options.has_req_styles())
+    .unwrap_or_else(|| config.req_style(&member.name))
+}
+
+/// Returns values of placeholders used in pre-release replacements of the crate.
+fn placeholders(member: &Member, config: &Config) -> Placeholders {
+  Placeholders {
+    crate_name: member.name.clone(),
+    version: member.version.clone(),
+    date: utils::today(),
+    tag_name: config.tag_name(&member.name, &member.version),
+  }
+}
+
+/// Prints the header with label, name, version and path of the crate.
+fn print_header(label: Text, member: &Member) {
+  info!(
+    "\n{} {} {} {}",
+    label,
+    auto().bold().blue().s(&member.name).reset(),
+    auto().bold().green().s('v').s(&member.version).reset(),
+    member.path
+  );
+}
+
+/// Executes `cargo publish` for all crates in the group, outcomes are recorded in the summary.
+///
+/// A single crate is published directly, multiple crates are published by at most `jobs` workers
+/// taking the next crate from the group as soon as they finish the previous one, each crate with
+/// its own target directory to avoid waiting for the build lock. No new crates are started after a failure.
+/// Target directories (`target/univer/jobs/<crate>`) are not shared with the workspace, so every
+/// concurrently published crate is verified with a full build of its dependencies; they are kept
+/// between runs, so only the first release pays the full cost.
+/// The output of each crate is logged to its own file.
+fn execute_publish(workspace: &Workspace, group: &[Member], dry_run: bool, rewritten: &[Utf8PathBuf], summary: &mut Summary) -> Result<()> {
+  let config = workspace.config();
+  if let [member] = group {
+    let started = Instant::now();
+    let result = retry::execute_cargo(
+      config,
+      &member.name,
+      &publish_args(config, dry_run, is_rewritten(member, rewritten)),
+      &member.manifest_dir,
+      &summary.log_path(&member.name),
+    );
+    summary.record(&member.name, dry_run, result.is_ok(), started.elapsed());
+    return result;
+  }
+  let next = AtomicUsize::new(0);
+  let failed = AtomicBool::new(false);
+  let outcomes = Mutex::new(vec![]);
+  std::thread::scope(|scope| {
+    for _ in 0..config.jobs.clamp(1, group.len()) {
+      scope.spawn(|| {
+        while !failed.load(Ordering::SeqCst) {
+          let index = next.fetch_add(1, Ordering::SeqCst);
+          let Some(member) = group.get(index) else {
+            break;
+          };
+          let mut args = publish_args(config, dry_run, is_rewritten(member, rewritten));
+          args.push("--target-dir".to_string());
+          args.push(workspace.univer_dir().join("jobs").join(&member.name).to_string());
+          let started = Instant::now();
+          let result = retry::execute_cargo(config, &member.name, &args, &member.manifest_dir, &summary.log_path(&member.name));
+          if result.is_err() {
+            failed.store(true, Ordering::SeqCst);
+          }
+          outcomes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((index, result.map_err(|reason| reason.to_string()), started.elapsed()));
+        }
+      });
+    }
+  });
+  let mut outcomes = outcomes.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+  outcomes.sort_by_key(|(index, _, _)| *index);
+  let mut failures = vec![];
+  for (index, result, duration) in outcomes {
+    let member = &group[index];
+    summary.record(&member.name, dry_run, result.is_ok(), duration);
+    if let Err(reason) = result {
+      failures.push(format!("'{}': {}", member.name, reason));
+    }
+  }
+  if !failures.is_empty() {
+    return Err(univer_error!("publishing crates failed: {}", failures.join(", ")));
   }
   Ok(())
 }
+
+/// Returns `true` when any of the files rewritten by pre-release replacements belongs to the crate.
+fn is_rewritten(member: &Member, rewritten: &[Utf8PathBuf]) -> bool {
+  member
+    .manifest_dir
+    .canonicalize_utf8()
+    .is_ok_and(|manifest_dir| rewritten.iter().any(|path| path.starts_with(&manifest_dir)))
+}
+
+/// Returns arguments passed to `cargo publish` command.
+fn publish_args(config: &Config, dry_run: bool, allow_dirty: bool) -> Vec<String> {
+  let mut args = vec!["publish".to_string()];
+  if dry_run {
+    args.push("--dry-run".to_string());
+  }
+  if let Some(registry) = &config.registry {
+    args.push("--registry".to_string());
+    args.push(registry.clone());
+  }
+  if allow_dirty {
+    args.push("--allow-dirty".to_string());
+  }
+  if output::is_quiet() {
+    args.push("--quiet".to_string());
+  }
+  args.push(format!("--color={}", output::cargo_color()));
+  args
+}
+
+/// Prints the preview of changes resulting from pre-release replacements.
+fn print_preview(changes: &[FileChange]) {
+  for change in changes {
+    for preview in &change.previews {
+      info!(
+        "{}  {}  {}",
+        auto().bold().s(&change.file).reset(),
+        preview.search.replace('\n', "\\n"),
+        auto().bold().green().s(preview.count).s(if preview.count == 1 { " match" } else { " matches" }).reset()
+      );
+      for (matched, replaced) in &preview.fragments {
+        for line in matched.lines() {
+          info!("{}", auto().red().s(format!("- {line}").trim_end()).reset());
+        }
+        for line in replaced.lines() {
+          info!("{}", auto().green().s(format!("+ {line}").trim_end()).reset());
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,218 @@
+//! # Pre-release replacements
+//!
+//! Implements replacements defined in cargo-release style
+//! `[package.metadata.release] pre-release-replacements` table.
+
+use crate::errors::{Result, univer_error};
+use crate::model::Member;
+use crate::{bump, utils};
+use cargo_metadata::camino::Utf8PathBuf;
+use regex::Regex;
+
+/// Single replacement to be applied to a file before publishing a crate.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+  /// Path to the file, relative to the directory containing crate's manifest.
+  pub file: String,
+  /// Regular expression to search for.
+  pub search: String,
+  /// Replacement text, may contain placeholders like `{{version}}`.
+  pub replace: String,
+  /// Exact number of expected matches.
+  pub exactly: Option<usize>,
+  /// Minimum number of expected matches.
+  pub min: Option<usize>,
+  /// Maximum number of expected matches.
+  pub max: Option<usize>,
+  /// Flag indicating if the replacement should be applied for pre-release versions.
+  pub prerelease: bool,
+}
+
+/// Values substituted for placeholders in replacement text.
+pub struct Placeholders {
+  /// Name of the crate, substituted for `{{crate_name}}`.
+  pub crate_name: String,
+  /// Version of the crate, substituted for `{{version}}`.
+  pub version: String,
+  /// Current date, substituted for `{{date}}`.
+  pub date: String,
+  /// Name of the release tag, substituted for `{{tag_name}}`.
+  pub tag_name: String,
+}
+
+impl Placeholders {
+  /// Substitutes all placeholders in the given text.
+  pub fn apply(&self, text: &str) -> String {
+    text
+      .replace("{{crate_name}}", &self.crate_name)
+      .replace("{{version}}", &self.version)
+      .replace("{{date}}", &self.date)
+      .replace("{{tag_name}}", &self.tag_name)
+  }
+}
+
+/// Change to be applied to a single file.
+pub struct FileChange {
+  /// Path to the changed file.
+  pub path: Utf8PathBuf,
+  /// Path to the file as specified in the manifest.
+  pub file: String,
+  /// Original content of the file.
+  pub original: String,
+  /// Content of the file after applying all replacements.
+  pub content: String,
+  /// Preview of each applied replacement as a list of removed and added fragments.
+  pub previews: Vec<Preview>,
+}
+
+/// Preview of a single replacement.
+pub struct Preview {
+  /// Searched regular expression.
+  pub search: String,
+  /// Number of matches.
+  pub count: usize,
+  /// Matched fragments with their replacements.
+  pub fragments: Vec<(String, String)>,
+}
+
+/// Loads pre-release replacements defined in the manifest of the specified member.
+pub fn load(member: &Member) -> Result<Vec<Replacement>> {
+  let manifest_toml = utils::parse_toml(&member.manifest_path)?;
+  let Some(replacements) = manifest_toml
+    .get("package")
+    .and_then(|package| package.get("metadata"))
+    .and_then(|metadata| metadata.get("release"))
+    .and_then(|release| release.get("pre-release-replacements"))
+  else {
+    return Ok(vec![]);
+  };
+  let Some(replacements) = replacements.as_array() else {
+    return Err(univer_error!("'pre-release-replacements' is not an array in crate '{}'", member.name));
+  };
+  let mut result = vec![];
+  for replacement in replacements {
+    let get_string = |key: &str| -> Result<String> {
+      let Some(value) = replacement.get(key) else {
+        return Err(univer_error!("missing '{}' in pre-release replacement in crate '{}'", key, member.name));
+      };
+      let Some(value) = value.as_str() else {
+        return Err(univer_error!("'{}' is not a string in pre-release replacement in crate '{}'", key, member.name));
+      };
+      Ok(value.to_string())
+    };
+    let get_count = |key: &str| -> Result<Option<usize>> {
+      let Some(value) = replacement.get(key) else {
+        return Ok(None);
+      };
+      let Some(value) = value.as_integer().and_then(|value| usize::try_from(value).ok()) else {
+        return Err(univer_error!(
+          "'{}' is not a non-negative integer in pre-release replacement in crate '{}'",
+          key,
+          member.name
+        ));
+      };
+      Ok(Some(value))
+    };
+    let prerelease = match replacement.get("prerelease") {
+      None => false,
+      Some(value) => {
+        let Some(value) = value.as_bool() else {
+          return Err(univer_error!("'prerelease' is not a boolean in pre-release replacement in crate '{}'", member.name));
+        };
+        value
+      }
+    };
+    result.push(Replacement {
+      file: get_string("file")?,
+      search: get_string("search")?,
+      replace: get_string("replace")?,
+      exactly: get_count("exactly")?,
+      min: get_count("min")?,
+      max: get_count("max")?,
+      prerelease,
+    });
+  }
+  Ok(result)
+}
+
+/// Prepares changes to files resulting from applying all replacements for the specified member.
+///
+/// No file is modified, the changes are only computed and validated,
+/// so when any replacement fails, none of the files is touched.
+pub fn prepare(member: &Member, replacements: &[Replacement], placeholders: &Placeholders) -> Result<Vec<FileChange>> {
+  let prerelease_version = bump::is_pre_release(&member.version);
+  let mut changes: Vec<FileChange> = vec![];
+  for replacement in replacements {
+    if prerelease_version && !replacement.prerelease {
+      continue;
+    }
+    let path = member.manifest_dir.join(&replacement.file);
+    let index = match changes.iter().position(|change| change.path == path) {
+      Some(index) => index,
+      None => {
+        let original = utils::read_file(&path)?;
+        changes.push(FileChange {
+          path,
+          file: replacement.file.clone(),
+          content: original.clone(),
+          original,
+          previews: vec![],
+        });
+        changes.len() - 1
+      }
+    };
+    let change = &mut changes[index];
+    let regex = Regex::new(&replacement.search).map_err(|e| univer_error!("invalid search pattern '{}' in crate '{}', reason: {}", replacement.search, member.name, e))?;
+    let replace = placeholders.apply(&replacement.replace);
+    let mut fragments = vec![];
+    for captures in regex.captures_iter(&change.content) {
+      let mut replaced = String::new();
+      captures.expand(&replace, &mut replaced);
+      fragments.push((captures[0].to_string(), replaced));
+    }
+    let count = fragments.len();
+    let file = &replacement.file;
+    let search = &replacement.search;
+    if let Some(exactly) = replacement.exactly {
+      if count != exactly {
+        return Err(univer_error!(
+          "expected exactly {exactly} match(es) for '{search}' in file '{file}' but found {count}, crate '{}'",
+          member.name
+        ));
+      }
+    } else {
+      let min = replacement.min.unwrap_or(1);
+      if count < min {
+        return Err(univer_error!(
+          "expected at least {min} match(es) for '{search}' in file '{file}' but found {count}, crate '{}'",
+          member.name
+        ));
+      }
+      if let Some(max) = replacement.max
+        && count > max
+      {
+        return Err(univer_error!(
+          "expected at most {max} match(es) for '{search}' in file '{file}' but found {count}, crate '{}'",
+          member.name
+        ));
+      }
+    }
+    change.content = regex.replace_all(&change.content, replace.as_str()).to_string();
+    change.previews.push(Preview {
+      search: search.clone(),
+      count,
+      fragments,
+    });
+  }
+  Ok(changes)
+}
+
+/// Writes prepared changes to files.
+pub fn apply(changes: &[FileChange]) -> Result<()> {
+  for change in changes {
+    if change.content != change.original {
+      utils::write_file(&change.path, &change.content)?;
+    }
+  }
+  Ok(())
+}
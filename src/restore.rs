@@ -0,0 +1,55 @@
+//! # Restoring the workspace manifest from automatic backups
+
+use crate::backup;
+use crate::errors::{Result, univer_error};
+use crate::output::{auto, info};
+use crate::utils;
+use crate::utils::RUST_MANIFEST_NAME;
+use antex::StyledText;
+use cargo_metadata::MetadataCommand;
+use cargo_metadata::camino::Utf8PathBuf;
+use std::path::Path;
+
+/// Lists backups of the workspace manifest and restores the selected one.
+///
+/// Backups are numbered from the newest one, starting with 1.
+/// When no backup is selected, backups are only listed.
+pub fn restore(manifest_dir: &Path, selected: Option<usize>, accept_all: bool) -> Result<()> {
+  let manifest_path = Utf8PathBuf::from_path_buf(manifest_dir.join(RUST_MANIFEST_NAME)).map_err(|path| univer_error!("invalid manifest path {}", path.display()))?;
+  // The manifest may be broken, so the default target directory is used when metadata could not be loaded.
+  let target_dir = MetadataCommand::new()
+    .manifest_path(&manifest_path)
+    .no_deps()
+    .exec()
+    .map(|metadata| metadata.target_directory)
+    .unwrap_or_else(|_| manifest_path.with_file_name("target"));
+  let univer_dir = target_dir.join("univer");
+  let backups = backup::list(&univer_dir)?;
+  if backups.is_empty() {
+    return Err(univer_error!("no backups found"));
+  }
+  info!();
+  info!("Backups:");
+  for (index, backup) in backups.iter().enumerate() {
+    info!(
+      "{:>3}  {}  {}",
+      auto().bold().s(index + 1).reset(),
+      backup.time(),
+      auto().bold().blue().s(&backup.command).reset()
+    );
+  }
+  let Some(selected) = selected else {
+    return Ok(());
+  };
+  let Some(backup) = selected.checked_sub(1).and_then(|index| backups.get(index)) else {
+    return Err(univer_error!("backup {} not found", selected));
+  };
+  info!();
+  if !utils::prompt(&format!("Restore backup {} created by '{}' at {}?", selected, backup.command, backup.time()), accept_all)? {
+    return Ok(());
+  }
+  // Restoring is undoable, the current manifest is backed up too.
+  let content = utils::read_file(&backup.path)?;
+  backup::create(&univer_dir, &manifest_path, "restore")?;
+  utils::write_file(&manifest_path, content)
+}
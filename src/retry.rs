@@ -0,0 +1,98 @@
+//! # Retrying cargo commands failed with transient errors
+
+use crate::config::Config;
+use crate::errors::{Result, univer_error};
+use crate::output::{debug, warning};
+use crate::utils;
+use crate::utils::Completion;
+use cargo_metadata::camino::Utf8Path;
+use regex::Regex;
+use std::time::Duration;
+
+/// Pattern of the error reported when the crate version is already published.
+const PUBLISHED_PATTERN: &str = r"already (exists|uploaded)";
+
+/// Patterns of errors that never go away when the command is retried, checked first.
+const PERMANENT_PATTERNS: &[&str] = &[
+  PUBLISHED_PATTERN,
+  r"could not compile",
+  r"error\[E\d+\]",
+  r"failed to verify package tarball",
+  r"failed to prepare local package",
+  r"\b(got|status|code):? 40[13]\b",
+];
+
+/// Patterns of transient errors, like network failures, server errors and rate limits.
+const TRANSIENT_PATTERNS: &[&str] = &[
+  r"\b(got|status|code):? 429\b",
+  r"too many requests",
+  r"\b(got|status|code):? 5\d\d\b",
+  r"timed out",
+  r"connection (refused|reset|closed|aborted)",
+  r"failed to connect",
+  r"could not resolve host",
+  r"network (failure|error|unreachable)",
+  r"spurious network error",
+  r"temporarily unavailable",
+];
+
+/// Returns `true` when the output of the failed command reports a transient error,
+/// so the command may succeed when retried.
+pub fn is_transient(output: &str) -> bool {
+  let matches = |patterns: &[&str]| {
+    patterns
+      .iter()
+      .any(|pattern| Regex::new(&format!("(?i){}", pattern)).is_ok_and(|regex| regex.is_match(output)))
+  };
+  !matches(PERMANENT_PATTERNS) && matches(TRANSIENT_PATTERNS)
+}
+
+/// Returns `true` when the output of the failed command reports that the crate version is already published.
+pub fn is_published(output: &str) -> bool {
+  Regex::new(&format!("(?i){}", PUBLISHED_PATTERN)).is_ok_and(|regex| regex.is_match(output))
+}
+
+/// Executes `cargo` with specified arguments like [utils::execute_logged_command], limited by the configured timeout.
+///
+/// The command failed with a transient error is retried up to the configured number of times,
+/// the delay before the next retry is doubled every time. The command killed after the timeout
+/// is always retried, other errors are classified using the output of the last attempt only,
+/// read back from the log file. When the retried command reports
+/// that the crate version is already published, the previous attempt succeeded (like when the upload
+/// was completed but the response was lost), so it is considered a success.
+pub fn execute_cargo(config: &Config, name: &str, args: &[String], dir: &Utf8Path, log_path: &Utf8Path) -> Result<()> {
+  let timeout = config.timeout.map(Duration::from_secs);
+  let mut delay = Duration::from_secs(config.retry_delay);
+  let mut attempt = 0;
+  loop {
+    let offset = std::fs::metadata(log_path).map(|metadata| metadata.len() as usize).unwrap_or_default();
+    let (reason, transient) = match utils::execute_logged_command("cargo", args, dir, &[], log_path.as_ref(), timeout) {
+      Ok(Completion::Finished) => return Ok(()),
+      // Hung commands, like uploads without response, are always worth retrying.
+      Ok(Completion::TimedOut(timeout)) => (univer_error!("command timed out after {}s and was killed", timeout.as_secs()), true),
+      Err(reason) => {
+        let content = utils::read_file(log_path).unwrap_or_default();
+        let output = format!("{}\n{}", content.get(offset..).unwrap_or_default(), reason);
+        if attempt > 0 && is_published(&output) {
+          warning!("'{}' is already published, the previous attempt succeeded", name);
+          return Ok(());
+        }
+        (reason, is_transient(&output))
+      }
+    };
+    if attempt >= config.retries || !transient {
+      debug!("not retrying '{}' after {} attempt(s)", name, attempt + 1);
+      return Err(reason);
+    }
+    attempt += 1;
+    warning!(
+      "publishing '{}' failed with a transient error, retry {} of {} in {}s",
+      name,
+      attempt,
+      config.retries,
+      delay.as_secs()
+    );
+    std::thread::sleep(delay);
+    delay = delay.saturating_mul(2);
+  }
+}
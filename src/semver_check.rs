@@ -0,0 +1,414 @@
+//! # Checking compatibility of the public API with the previous release
+
+use crate::bump::Level;
+use crate::changed;
+use crate::errors::{Result, univer_error};
+use crate::model::{Member, Workspace};
+use crate::output::{self, auto, info};
+use crate::utils;
+use antex::StyledText;
+use cargo_metadata::camino::Utf8Path;
+use cargo_metadata::semver::Version;
+use cargo_metadata::{MetadataCommand, TargetKind};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Name of the directory with semver check files in the univer working directory.
+const SEMVER_CHECK_DIR_NAME: &str = "semver-check";
+
+/// Toolchain used to generate rustdoc JSON, the output format is unstable.
+const TOOLCHAIN: &str = "+nightly";
+
+/// Version of rustdoc JSON format the public API is extracted from, other versions may have different layouts.
+const FORMAT_VERSION: u64 = 57;
+
+/// Keys in rustdoc JSON referring to other items (by identifiers), these items are compared separately.
+const ID_KEYS: [&str; 7] = ["id", "items", "fields", "variants", "impls", "implementations", "tuple"];
+
+/// Public API of a crate, signatures of items by kind and path, like `fn alpha::add`.
+type Api = BTreeMap<String, Value>;
+
+/// Differences between the public API of the previous release and the current one.
+#[derive(Debug, Default, Clone)]
+pub struct ApiDiff {
+  /// Items added to the public API.
+  pub added: Vec<String>,
+  /// Items removed from the public API.
+  pub removed: Vec<String>,
+  /// Items with changed signatures.
+  pub changed: Vec<String>,
+}
+
+impl ApiDiff {
+  /// Compares the public API of the previous release with the current one.
+  fn new(baseline: &Api, current: &Api) -> Self {
+    let mut diff = Self::default();
+    for (item, signature) in baseline {
+      match current.get(item) {
+        None => diff.removed.push(item.clone()),
+        Some(current_signature) if current_signature != signature => diff.changed.push(item.clone()),
+        _ => {}
+      }
+    }
+    diff.added = current.keys().filter(|item| !baseline.contains_key(*item)).cloned().collect();
+    diff
+  }
+
+  /// Returns the minimal version bump required by the differences.
+  pub fn required_level(&self) -> Level {
+    if !self.removed.is_empty() || !self.changed.is_empty() {
+      Level::Major
+    } else if !self.added.is_empty() {
+      Level::Minor
+    } else {
+      Level::Patch
+    }
+  }
+}
+
+/// Result of checking a single crate.
+enum Outcome {
+  /// The crate was not checked, with the reason.
+  Skipped(&'static str),
+  /// The crate was compared with the version from the previous release.
+  Checked(Version, ApiDiff),
+}
+
+/// Compares the public API of crates with the previous release and checks if the planned version bump is large enough.
+///
+/// The previous release of each crate is checked out from the release tag (or the specified git reference)
+/// into a git worktree. Public APIs are extracted from rustdoc JSON, generated using the nightly toolchain.
+/// Removed or changed items require a major bump, added items require a minor bump,
+/// leading zeros in versions are not significant, like in Cargo. Names of failed crates are returned.
+pub fn check(workspace: &Workspace, members: &[Member], since: Option<&str>) -> Result<Vec<String>> {
+  let root = workspace.root();
+  let check_dir = workspace.univer_dir().join(SEMVER_CHECK_DIR_NAME);
+  let worktree_dir = check_dir.join("baseline");
+  let output = changed::git(root, &["rev-parse", "--show-prefix"])?;
+  if !output.status.success() {
+    return Err(univer_error!("git repository not found in {}", root));
+  }
+  // Path of the workspace root relative to the root of the git repository.
+  let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  let current_libraries = libraries(workspace.manifest_path())?;
+  // Crates released with the same tag are compared with the same worktree.
+  let mut outcomes = HashMap::new();
+  let mut baselines: BTreeMap<String, Vec<&Member>> = BTreeMap::new();
+  for member in members {
+    let tag = match since {
+      Some(since) => Some(since.to_string()),
+      None => changed::release_tag(workspace, member)?,
+    };
+    match (tag, current_libraries.get(&member.name)) {
+      (_, None | Some((_, None))) => {
+        outcomes.insert(member.name.clone(), Outcome::Skipped("no library"));
+      }
+      (None, _) => {
+        outcomes.insert(member.name.clone(), Outcome::Skipped("not released yet"));
+      }
+      (Some(tag), _) => baselines.entry(tag).or_default().push(member),
+    }
+  }
+  for (tag, baseline_members) in baselines {
+    remove_worktree(root, &worktree_dir)?;
+    let output = changed::git(root, &["worktree", "add", "--detach", "--force", worktree_dir.as_str(), &tag])?;
+    if !output.status.success() {
+      return Err(univer_error!("checking out '{}' failed, reason: {}", tag, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let result = (|| -> Result<()> {
+      let baseline_libraries = libraries(&worktree_dir.join(&prefix).join(utils::RUST_MANIFEST_NAME))?;
+      for member in baseline_members {
+        let Some((baseline_version, Some(baseline_library))) = baseline_libraries.get(&member.name) else {
+          outcomes.insert(member.name.clone(), Outcome::Skipped("not released yet"));
+          continue;
+        };
+        let baseline = public_api(&worktree_dir.join(&prefix), &check_dir.join("target-baseline"), &member.name, baseline_library)?;
+        let current_library = current_libraries[&member.name].1.as_deref().unwrap_or_default();
+        let current = public_api(root, &check_dir.join("target"), &member.name, current_library)?;
+        outcomes.insert(member.name.clone(), Outcome::Checked(baseline_version.clone(), ApiDiff::new(&baseline, &current)));
+      }
+      Ok(())
+    })();
+    // The worktree is removed even when the check failed.
+    remove_worktree(root, &worktree_dir)?;
+    result?;
+  }
+  // Report results in the order of publishing.
+  let mut failed = vec![];
+  info!("Semver check:");
+  for member in members {
+    let name = auto().bold().blue().s(&member.name).reset();
+    let version = auto().bold().green().s('v').s(&member.version).reset();
+    match &outcomes[&member.name] {
+      Outcome::Skipped(reason) => info!("{}  {}  skipped, {}", name, version, reason),
+      Outcome::Checked(baseline_version, diff) => {
+        let planned_version = Version::parse(&member.version).map_err(|e| univer_error!("invalid version '{}', reason: {}", member.version, e))?;
+        let required_level = diff.required_level();
+        if weight(bump_level(baseline_version, &planned_version)) >= weight(required_level) {
+          info!("{}  v{} -> {}  {}", name, baseline_version, version, auto().green().s("ok").reset());
+        } else {
+          info!(
+            "{}  v{} -> {}  {}, {} bump required",
+            name,
+            baseline_version,
+            version,
+            auto().red().s("failed").reset(),
+            level_name(required_level)
+          );
+          failed.push(member.name.clone());
+        }
+        for item in &diff.removed {
+          info!("  {}", auto().red().s("- ").s(item).reset());
+        }
+        for item in &diff.changed {
+          info!("  {}", auto().yellow().s("~ ").s(item).reset());
+        }
+        for item in &diff.added {
+          info!("  {}", auto().green().s("+ ").s(item).reset());
+        }
+      }
+    }
+  }
+  Ok(failed)
+}
+
+/// Returns the level of the bump between two versions, leading zeros are not significant,
+/// so `0.3.1` -> `0.4.0` is a major bump and `0.3.1` -> `0.3.2` is a minor one.
+fn bump_level(baseline: &Version, planned: &Version) -> Level {
+  let baseline = [baseline.major, baseline.minor, baseline.patch];
+  let planned = [planned.major, planned.minor, planned.patch];
+  let start = baseline.iter().take(2).take_while(|part| **part == 0).count();
+  match (start..3).find(|index| baseline[*index] != planned[*index]).map(|index| index - start) {
+    Some(0) => Level::Major,
+    Some(1) => Level::Minor,
+    _ => Level::Patch,
+  }
+}
+
+/// Returns the weight of the bump level, greater for more significant levels.
+fn weight(level: Level) -> u8 {
+  match level {
+    Level::Major => 2,
+    Level::Minor => 1,
+    Level::Patch => 0,
+  }
+}
+
+/// Returns the name of the bump level.
+fn level_name(level: Level) -> &'static str {
+  match level {
+    Level::Major => "major",
+    Level::Minor => "minor",
+    Level::Patch => "patch",
+  }
+}
+
+/// Returns versions and library target names of workspace packages, by package name.
+fn libraries(manifest_path: &Utf8Path) -> Result<HashMap<String, (Version, Option<String>)>> {
+  let metadata = MetadataCommand::new()
+    .manifest_path(manifest_path)
+    .no_deps()
+    .exec()
+    .map_err(|e| univer_error!("loading metadata from {} failed, reason: {}", manifest_path, e))?;
+  Ok(
+    metadata
+      .workspace_packages()
+      .iter()
+      .map(|package| {
+        let library = package
+          .targets
+          .iter()
+          .find(|target| target.is_kind(TargetKind::Lib) || target.is_kind(TargetKind::RLib))
+          .map(|target| target.name.replace('-', "_"));
+        (package.name.to_string(), (package.version.clone(), library))
+      })
+      .collect(),
+  )
+}
+
+/// Generates rustdoc JSON for the library of the crate and returns its public API.
+fn public_api(workspace_dir: &Utf8Path, target_dir: &Utf8Path, name: &str, library: &str) -> Result<Api> {
+  let color = format!("--color={}", output::cargo_color());
+  let args = [
+    TOOLCHAIN,
+    "rustdoc",
+    "--package",
+    name,
+    "--lib",
+    &color,
+    "--target-dir",
+    target_dir.as_str(),
+    "--",
+    "-Z",
+    "unstable-options",
+    "--output-format",
+    "json",
+  ];
+  utils::execute_command("cargo", args, workspace_dir, &[]).map_err(|e| univer_error!("generating rustdoc JSON for crate '{}' failed, reason: {}", name, e))?;
+  let json_path = target_dir.join("doc").join(format!("{}.json", library));
+  let json = serde_json::from_str::<Value>(&utils::read_file(&json_path)?).map_err(|e| univer_error!("invalid rustdoc JSON in {}, reason: {}", json_path, e))?;
+  // Items of unsupported format would be silently missing in the public API.
+  let format_version = json["format_version"].as_u64().unwrap_or_default();
+  if format_version != FORMAT_VERSION {
+    return Err(univer_error!(
+      "unsupported rustdoc JSON format version {} for crate '{}', expected version {}, update univer or the nightly toolchain",
+      format_version,
+      name,
+      FORMAT_VERSION
+    ));
+  }
+  let mut api = Api::new();
+  let root = json["root"].to_string();
+  collect_module(&json["index"], &root, library, &mut api, &mut BTreeSet::new());
+  Ok(api)
+}
+
+/// Collects public items of the module, re-exported items are collected under their public paths.
+fn collect_module(index: &Value, id: &str, path: &str, api: &mut Api, visited: &mut BTreeSet<String>) {
+  // Glob re-exports may form cycles.
+  if !visited.insert(format!("{}#{}", id, path)) {
+    return;
+  }
+  for item_id in index[id]["inner"]["module"]["items"].as_array().into_iter().flatten() {
+    let item = &index[item_id.to_string()];
+    if item["visibility"] != "public" {
+      continue;
+    }
+    if let Some(import) = item["inner"].get("use") {
+      let target_id = import["id"].to_string();
+      match index.get(&target_id) {
+        Some(target) if import["is_glob"] == true && target["inner"].get("module").is_some() => collect_module(index, &target_id, path, api, visited),
+        Some(target) => collect_item(index, target, &format!("{}::{}", path, import["name"].as_str().unwrap_or_default()), api, visited),
+        // Items re-exported from other crates are compared by their source paths.
+        None => {
+          api.insert(format!("use {}::{}", path, import["name"].as_str().unwrap_or_default()), import["source"].clone());
+        }
+      }
+    } else if let Some(name) = item["name"].as_str() {
+      collect_item(index, item, &format!("{}::{}", path, name), api, visited);
+    }
+  }
+}
+
+/// Collects the public item and its members, like fields, variants, associated items and methods.
+fn collect_item(index: &Value, item: &Value, path: &str, api: &mut Api, visited: &mut BTreeSet<String>) {
+  let Some((kind, inner)) = item["inner"].as_object().and_then(|inner| inner.iter().next()) else {
+    return;
+  };
+  // Values of constants may change without breaking the API.
+  let signature = if kind == "constant" { strip(&inner["type"]) } else { strip(inner) };
+  api.insert(format!("{} {}", kind_name(kind), path), signature);
+  let members = |key: &str| {
+    inner[key]
+      .as_array()
+      .into_iter()
+      .flatten()
+      .filter_map(|id| index.get(id.to_string()))
+      .collect::<Vec<&Value>>()
+  };
+  match kind.as_str() {
+    "module" => collect_module(index, &item["id"].to_string(), path, api, visited),
+    "struct" | "union" => {
+      let fields = inner["kind"]["plain"]["fields"]
+        .as_array()
+        .or(inner["kind"]["tuple"].as_array())
+        .or(inner["fields"].as_array());
+      for field in fields.into_iter().flatten().filter_map(|id| index.get(id.to_string())) {
+        if field["visibility"] == "public" {
+          collect_item(index, field, &format!("{}::{}", path, field["name"].as_str().unwrap_or_default()), api, visited);
+        }
+      }
+    }
+    "enum" => {
+      for variant in members("variants") {
+        collect_item(index, variant, &format!("{}::{}", path, variant["name"].as_str().unwrap_or_default()), api, visited);
+      }
+    }
+    "variant" => {
+      let fields = inner["kind"]["struct"]["fields"].as_array().or(inner["kind"]["tuple"].as_array());
+      for field in fields.into_iter().flatten().filter_map(|id| index.get(id.to_string())) {
+        collect_item(index, field, &format!("{}::{}", path, field["name"].as_str().unwrap_or_default()), api, visited);
+      }
+    }
+    "trait" => {
+      for trait_item in members("items") {
+        collect_item(index, trait_item, &format!("{}::{}", path, trait_item["name"].as_str().unwrap_or_default()), api, visited);
+      }
+    }
+    _ => {}
+  }
+  // Inherent methods and implemented traits (except automatic and blanket implementations).
+  for implementation in members("impls") {
+    let implementation = &implementation["inner"]["impl"];
+    if implementation["is_synthetic"] == true || !implementation["blanket_impl"].is_null() {
+      continue;
+    }
+    if let Some(trait_path) = implementation["trait"]["path"].as_str() {
+      api.insert(format!("impl {} for {}", trait_path, path), strip(&implementation["generics"]));
+      continue;
+    }
+    for method in implementation["items"].as_array().into_iter().flatten().filter_map(|id| index.get(id.to_string())) {
+      if method["visibility"] == "public" {
+        collect_item(index, method, &format!("{}::{}", path, method["name"].as_str().unwrap_or_default()), api, visited);
+      }
+    }
+  }
+}
+
+/// Returns the short name of the item kind, like `fn` for functions.
+fn kind_name(kind: &str) -> &str {
+  match kind {
+    "function" => "fn",
+    "module" => "mod",
+    "constant" | "assoc_const" => "const",
+    "type_alias" | "assoc_type" => "type",
+    "struct_field" => "field",
+    "macro" | "proc_macro" => "macro",
+    other => other,
+  }
+}
+
+/// Returns the signature with references to other items removed, identifiers differ between builds.
+fn strip(value: &Value) -> Value {
+  match value {
+    Value::Object(map) => Value::Object(
+      map
+        .iter()
+        .map(|(key, value)| {
+          (
+            key.clone(),
+            if ID_KEYS.contains(&key.as_str()) && is_reference(value) {
+              Value::Null
+            } else {
+              strip(value)
+            },
+          )
+        })
+        .collect(),
+    ),
+    Value::Array(values) => Value::Array(values.iter().map(strip).collect()),
+    other => other.clone(),
+  }
+}
+
+/// Returns `true` when the value is an identifier of an item or a list of identifiers,
+/// missing identifiers (like stripped fields) are `null`.
+fn is_reference(value: &Value) -> bool {
+  match value {
+    Value::Number(_) | Value::Null => true,
+    Value::Array(values) => values.iter().all(|value| value.is_number() || value.is_null()),
+    _ => false,
+  }
+}
+
+/// Removes the worktree with the previous release, left from previous checks.
+fn remove_worktree(root: &Utf8Path, worktree_dir: &Utf8Path) -> Result<()> {
+  if worktree_dir.exists() {
+    changed::git(root, &["worktree", "remove", "--force", worktree_dir.as_str()])?;
+    if worktree_dir.exists() {
+      std::fs::remove_dir_all(worktree_dir).map_err(|e| univer_error!("failed to remove directory {}, reason: {}", worktree_dir, e))?;
+    }
+  }
+  changed::git(root, &["worktree", "prune"])?;
+  Ok(())
+}
@@ -0,0 +1,167 @@
+//! # Summary of cargo commands executed while publishing
+
+use crate::errors::{Result, univer_error};
+use crate::model::{Member, Workspace};
+use crate::output::{auto, auto_stderr, info};
+use crate::utils;
+use antex::{StyledText, Text};
+use cargo_metadata::camino::Utf8PathBuf;
+use std::time::Duration;
+
+/// Name of the directory with logs of cargo commands in the univer working directory.
+const LOGS_DIR_NAME: &str = "logs";
+
+/// Number of the last lines of the log reported for failed crates.
+const ERROR_LINES: usize = 10;
+
+/// Outcome of a single cargo command.
+#[derive(Debug, Copy, Clone)]
+struct Step {
+  /// Flag indicating if the command succeeded.
+  success: bool,
+  /// Time spent executing the command.
+  duration: Duration,
+}
+
+impl std::fmt::Display for Step {
+  /// Implementation of [Display](std::fmt::Display) trait for [Step].
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {:.1}s", if self.success { "ok" } else { "failed" }, self.duration.as_secs_f64())
+  }
+}
+
+/// Outcomes of cargo commands executed for a single crate.
+#[derive(Debug, Clone)]
+struct Record {
+  /// Name of the crate.
+  name: String,
+  /// Version of the crate.
+  version: String,
+  /// Outcome of `cargo publish --dry-run`.
+  dry_run: Option<Step>,
+  /// Outcome of `cargo publish`.
+  publish: Option<Step>,
+}
+
+/// Summary of cargo commands executed for crates, in the order of publishing.
+#[derive(Debug, Clone)]
+pub struct Summary {
+  /// Directory with logs of cargo commands, one file per crate.
+  logs_dir: Utf8PathBuf,
+  /// Outcomes of commands, one record per crate.
+  records: Vec<Record>,
+}
+
+impl Summary {
+  /// Creates the summary for crates to be published, logs left from previous runs are removed.
+  pub fn new(workspace: &Workspace, members: &[Member]) -> Result<Self> {
+    let logs_dir = workspace.univer_dir().join(LOGS_DIR_NAME);
+    std::fs::create_dir_all(&logs_dir).map_err(|e| univer_error!("failed to create directory {}, reason: {}", logs_dir, e))?;
+    let summary = Self {
+      logs_dir,
+      records: members
+        .iter()
+        .map(|member| Record {
+          name: member.name.clone(),
+          version: member.version.clone(),
+          dry_run: None,
+          publish: None,
+        })
+        .collect(),
+    };
+    for member in members {
+      let log_path = summary.log_path(&member.name);
+      if log_path.exists() {
+        std::fs::remove_file(&log_path).map_err(|e| univer_error!("failed to remove file {}, reason: {}", log_path, e))?;
+      }
+    }
+    Ok(summary)
+  }
+
+  /// Returns the path of the log file of the crate.
+  pub fn log_path(&self, name: &str) -> Utf8PathBuf {
+    self.logs_dir.join(format!("{}.log", name))
+  }
+
+  /// Records the outcome of `cargo publish` (or `cargo publish --dry-run`) executed for the crate.
+  pub fn record(&mut self, name: &str, dry_run: bool, success: bool, duration: Duration) {
+    if let Some(record) = self.records.iter_mut().find(|record| record.name == name) {
+      let step = Some(Step { success, duration });
+      if dry_run {
+        record.dry_run = step;
+      } else {
+        record.publish = step;
+      }
+    }
+  }
+
+  /// Prints the table with outcomes of executed commands, followed by the last lines of logs of failed crates.
+  ///
+  /// Successful crates are reported unless the output is quiet, failed crates and excerpts of their logs
+  /// are always reported to the standard error. Nothing is printed when no command was executed.
+  pub fn print(&self) {
+    if self.records.iter().all(|record| record.dry_run.is_none() && record.publish.is_none()) {
+      return;
+    }
+    let cell = |step: &Option<Step>| step.map(|step| step.to_string()).unwrap_or_else(|| "-".to_string());
+    let rows = self
+      .records
+      .iter()
+      .map(|record| [record.name.clone(), format!("v{}", record.version), cell(&record.dry_run), cell(&record.publish)])
+      .collect::<Vec<[String; 4]>>();
+    let header = ["crate", "version", "dry-run", "publish"].map(|title| title.to_string());
+    let widths = (0..3)
+      .map(|index| rows.iter().chain([&header]).map(|row| row[index].len()).max().unwrap_or_default())
+      .collect::<Vec<usize>>();
+    // Cells are padded before styling, the last column is not padded.
+    let pad = |row: &[String; 4], index: usize| format!("{:<1$}", row[index], widths[index]);
+    let line = |text: Text, row: &[String; 4]| {
+      let status = |text: Text, index: usize, cell: String| match row[index].split(' ').next() {
+        Some("ok") => text.green().s(cell).reset(),
+        Some("failed") => text.red().s(cell).reset(),
+        _ => text.s(cell).reset(),
+      };
+      let text = text.bold().blue().s(pad(row, 0)).reset().s("  ").bold().green().s(pad(row, 1)).reset().s("  ");
+      let text = status(text, 2, pad(row, 2)).s("  ");
+      status(text, 3, row[3].clone())
+    };
+    let heading = |text: Text| {
+      text
+        .bold()
+        .s(format!("{}  {}  {}  {}", pad(&header, 0), pad(&header, 1), pad(&header, 2), header[3]))
+        .reset()
+    };
+    let (failed, succeeded): (Vec<_>, Vec<_>) = self
+      .records
+      .iter()
+      .zip(&rows)
+      .partition(|(record, _)| record.dry_run.iter().chain(&record.publish).any(|step| !step.success));
+    if !succeeded.is_empty() {
+      info!();
+      info!("Summary:");
+      info!("{}", heading(auto()));
+      for (_, row) in &succeeded {
+        info!("{}", line(auto(), row));
+      }
+    }
+    if failed.is_empty() {
+      return;
+    }
+    eprintln!();
+    eprintln!("Failed:");
+    eprintln!("{}", heading(auto_stderr()));
+    for (_, row) in &failed {
+      eprintln!("{}", line(auto_stderr(), row));
+    }
+    for (record, _) in &failed {
+      let log_path = self.log_path(&record.name);
+      eprintln!();
+      eprintln!("{} failed, last lines of {}:", auto_stderr().bold().blue().s(&record.name).reset(), log_path);
+      let content = utils::read_file(&log_path).unwrap_or_default();
+      let lines = content.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<&str>>();
+      for line in &lines[lines.len().saturating_sub(ERROR_LINES)..] {
+        eprintln!("  {}", auto_stderr().red().s(line).reset());
+      }
+    }
+  }
+}
@@ -1,15 +1,27 @@
+use crate::config::SortOrder;
 use crate::errors::{Result, univer_error};
-use crate::model::Member;
-use antex::{StyledText, Text, auto};
+use crate::model::{DependencyKind, Member};
+use crate::output::{auto, debug};
+use antex::{StyledText, Text};
+use petgraph::Direction;
 use petgraph::graph::{DiGraph, NodeIndex};
-use std::collections::HashMap;
+use petgraph::visit::EdgeRef;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
 use std::io;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Default name of Rust manifest.
 pub const RUST_MANIFEST_NAME: &str = "Cargo.toml";
 
+/// Interval of checking if the command executed with timeout has finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 /// Reads the content of the file into string.
 pub fn read_file(file_name: impl Into<PathBuf>) -> Result<String> {
   let path = file_name.into();
@@ -17,9 +29,20 @@ pub fn read_file(file_name: impl Into<PathBuf>) -> Result<String> {
 }
 
 /// Writes string content to file.
+///
+/// The content is written to a temporary file first, which is then renamed,
+/// so the file is never left partially written.
 pub fn write_file(file_name: impl Into<PathBuf>, contents: impl AsRef<str>) -> Result<()> {
   let path = file_name.into();
-  std::fs::write(&path, contents.as_ref()).map_err(|e| univer_error!("failed to write text file {}, reason: {}", path.display(), e))
+  debug!("writing file {}", path.display());
+  let mut temporary_file_name = path.file_name().unwrap_or_default().to_os_string();
+  temporary_file_name.push(".univer.tmp");
+  let temporary_path = path.with_file_name(temporary_file_name);
+  std::fs::write(&temporary_path, contents.as_ref()).map_err(|e| univer_error!("failed to write text file {}, reason: {}", temporary_path.display(), e))?;
+  std::fs::rename(&temporary_path, &path).map_err(|e| {
+    let _ = std::fs::remove_file(&temporary_path);
+    univer_error!("failed to write text file {}, reason: {}", path.display(), e)
+  })
 }
 
 /// Parses TOML file.
@@ -28,30 +51,118 @@ pub fn parse_toml(file_name: impl Into<PathBuf>) -> Result<toml::Value> {
   toml::from_str(&read_file(&path)?).map_err(|e| univer_error!("failed to parse TOML file {}, reason {}", path.display(), e.to_string()))
 }
 
-/// Returns members sorted in the publishing order.
-pub fn sort(members: Vec<Member>) -> Vec<Member> {
-  let mut graph = DiGraph::<Member, ()>::new();
+/// Returns the number of seconds elapsed since Unix epoch.
+pub fn timestamp() -> u64 {
+  std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+/// Returns the current UTC date in `YYYY-MM-DD` format.
+pub fn today() -> String {
+  format_timestamp(timestamp())[..10].to_string()
+}
+
+/// Formats the number of seconds elapsed since Unix epoch as UTC date and time in `YYYY-MM-DD HH:MM:SS` format.
+pub fn format_timestamp(seconds: u64) -> String {
+  // Convert the number of days since epoch into civil date.
+  let days = (seconds / 86_400) as i64 + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days.rem_euclid(146_097);
+  let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let mp = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+  let time = seconds % 86_400;
+  format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, time / 3_600, time % 3_600 / 60, time % 60)
+}
+
+/// Builds the dependency graph of members.
+///
+/// Edges lead from the dependency to the dependent member and are labeled with dependency kind.
+pub fn dependency_graph(members: &[Member]) -> DiGraph<Member, DependencyKind> {
+  let mut graph = DiGraph::<Member, DependencyKind>::new();
   let mut nodes: HashMap<String, NodeIndex> = HashMap::new();
   // Add nodes.
-  for member in &members {
+  for member in members {
     let node_index = graph.add_node(member.clone());
     nodes.insert(member.name.clone(), node_index);
   }
   // Add edges.
-  for member in &members {
+  for member in members {
     let member_node_index = nodes.get(&member.name).unwrap();
     for dependency in &member.dependencies {
-      let dependency_node_index = nodes.get(&dependency.name).unwrap();
-      graph.add_edge(*dependency_node_index, *member_node_index, ());
+      // Dependencies to members that are not in the graph are skipped.
+      if let Some(dependency_node_index) = nodes.get(&dependency.name) {
+        graph.add_edge(*dependency_node_index, *member_node_index, dependency.kind);
+      }
     }
   }
+  graph
+}
+
+/// Returns members sorted in the publishing order.
+///
+/// Members are sorted topologically, when more members are ready to be published,
+/// the tie is broken using the specified order, so the result is always the same.
+pub fn sort(members: Vec<Member>, order: SortOrder) -> Result<Vec<Member>> {
+  let graph = dependency_graph(&members);
+  let sort_key = |node_index: NodeIndex| {
+    let member = &graph[node_index];
+    match order {
+      SortOrder::Alphabetical => (0, member.name.clone()),
+      SortOrder::Manifest => (member.position, member.name.clone()),
+    }
+  };
+  let mut in_degrees = graph
+    .node_indices()
+    .map(|node_index| graph.edges_directed(node_index, Direction::Incoming).count())
+    .collect::<Vec<usize>>();
+  let mut ready = graph
+    .node_indices()
+    .filter(|node_index| in_degrees[node_index.index()] == 0)
+    .map(|node_index| (sort_key(node_index), node_index))
+    .collect::<BTreeSet<_>>();
   let mut sorted_members = vec![];
-  let node_indexes = petgraph::algo::toposort(&graph, None).unwrap();
-  for node_index in node_indexes {
-    let member = graph.node_weight(node_index).unwrap().to_owned();
-    sorted_members.push(member);
+  while let Some((_, node_index)) = ready.pop_first() {
+    sorted_members.push(graph[node_index].clone());
+    for edge in graph.edges_directed(node_index, Direction::Outgoing) {
+      let target = edge.target();
+      in_degrees[target.index()] -= 1;
+      if in_degrees[target.index()] == 0 {
+        ready.insert((sort_key(target), target));
+      }
+    }
+  }
+  if sorted_members.len() < graph.node_count() {
+    let mut cycle = graph
+      .node_indices()
+      .filter(|node_index| in_degrees[node_index.index()] > 0)
+      .map(|node_index| graph[node_index].name.clone())
+      .collect::<Vec<String>>();
+    cycle.sort();
+    return Err(univer_error!("dependency cycle detected between crates: {}", cycle.join(", ")));
+  }
+  Ok(sorted_members)
+}
+
+/// Returns the topological level (depth) of each sorted member.
+///
+/// Members in the same level do not depend on each other, so they can be published concurrently.
+/// The level of a member is the length of the longest path to it from a member without dependencies.
+pub fn levels(sorted_members: &[Member]) -> HashMap<String, usize> {
+  let mut levels: HashMap<String, usize> = HashMap::new();
+  for member in sorted_members {
+    let level = member
+      .dependencies
+      .iter()
+      .filter_map(|dependency| levels.get(&dependency.name))
+      .map(|level| level + 1)
+      .max()
+      .unwrap_or_default();
+    levels.insert(member.name.clone(), level);
   }
-  sorted_members
+  levels
 }
 
 pub fn prompt(message: &str, accept: bool) -> Result<bool> {
@@ -75,3 +186,188 @@ pub fn prompt(message: &str, accept: bool) -> Result<bool> {
     }
   }
 }
+
+/// Executes a command in the specified directory with additional environment variables.
+pub fn execute_command<S, A, P>(program: S, args: A, dir: P, envs: &[(String, String)]) -> Result<()>
+where
+  S: AsRef<OsStr>,
+  A: IntoIterator,
+  A::Item: AsRef<OsStr>,
+  P: AsRef<Path>,
+{
+  let args = args.into_iter().map(|arg| arg.as_ref().to_os_string()).collect::<Vec<OsString>>();
+  debug!(
+    "executing: {} {} (in {})",
+    program.as_ref().to_string_lossy(),
+    args.iter().map(|arg| arg.to_string_lossy()).collect::<Vec<_>>().join(" "),
+    dir.as_ref().display()
+  );
+  let mut command = std::process::Command::new(program);
+  let mut child = command
+    .args(args)
+    .envs(envs.iter().map(|(key, value)| (key, value)))
+    .current_dir(dir)
+    .stdin(std::process::Stdio::inherit())
+    .stdout(std::process::Stdio::inherit())
+    .stderr(std::process::Stdio::inherit())
+    .spawn()
+    .map_err(|e| univer_error!("{}", e))?;
+  let exit_status = child.wait().map_err(|e| univer_error!("{}", e))?;
+  if !exit_status.success() {
+    return Err(univer_error!("executing command failed with status code: {}", exit_status));
+  }
+  Ok(())
+}
+
+/// Completion of the command executed with a timeout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Completion {
+  /// The command finished successfully.
+  Finished,
+  /// The command was still running after the timeout and was killed.
+  TimedOut(Duration),
+}
+
+/// Executes a command like [execute_command], the output is streamed live and appended to the log file.
+///
+/// Styling escape sequences are removed from the output written to the log file.
+/// The command still running after the timeout is killed together with processes it started
+/// (like compilers and build scripts), and [Completion::TimedOut] is returned. Processes left
+/// holding the output open do not block returning, their remaining output is not waited for.
+pub fn execute_logged_command<S, A, P>(program: S, args: A, dir: P, envs: &[(String, String)], log_path: &Path, timeout: Option<Duration>) -> Result<Completion>
+where
+  S: AsRef<OsStr>,
+  A: IntoIterator,
+  A::Item: AsRef<OsStr>,
+  P: AsRef<Path>,
+{
+  let args = args.into_iter().map(|arg| arg.as_ref().to_os_string()).collect::<Vec<OsString>>();
+  let command_line = format!(
+    "{} {}",
+    program.as_ref().to_string_lossy(),
+    args.iter().map(|arg| arg.to_string_lossy()).collect::<Vec<_>>().join(" ")
+  );
+  debug!("executing: {} (in {}), logged to {}", command_line, dir.as_ref().display(), log_path.display());
+  let mut log_file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(log_path)
+    .map_err(|e| univer_error!("failed to open log file {}, reason: {}", log_path.display(), e))?;
+  writeln!(log_file, "$ {}", command_line).map_err(|e| univer_error!("failed to write log file {}, reason: {}", log_path.display(), e))?;
+  let log_file = Arc::new(Mutex::new(log_file));
+  let mut command = std::process::Command::new(program);
+  let mut child = command
+    .args(args)
+    .envs(envs.iter().map(|(key, value)| (key, value)))
+    .current_dir(dir)
+    .stdin(std::process::Stdio::inherit())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .map_err(|e| univer_error!("{}", e))?;
+  // Readers are not scoped, so they can be abandoned when the killed command leaves the output open.
+  let mut readers = vec![];
+  if let Some(stdout) = child.stdout.take() {
+    let log_file = Arc::clone(&log_file);
+    readers.push(std::thread::spawn(move || tee(stdout, io::stdout(), &log_file)));
+  }
+  if let Some(stderr) = child.stderr.take() {
+    let log_file = Arc::clone(&log_file);
+    readers.push(std::thread::spawn(move || tee(stderr, io::stderr(), &log_file)));
+  }
+  let started = Instant::now();
+  let exit_status = match timeout {
+    None => child.wait().map_err(|e| univer_error!("{}", e))?,
+    Some(timeout) => loop {
+      // Poll the child, so it can be killed when the time is up.
+      if let Some(exit_status) = child.try_wait().map_err(|e| univer_error!("{}", e))? {
+        break exit_status;
+      }
+      if started.elapsed() >= timeout {
+        kill_tree(&mut child);
+        if let Ok(mut log_file) = log_file.lock() {
+          let _ = writeln!(log_file, "command timed out after {}s and was killed", timeout.as_secs());
+        }
+        return Ok(Completion::TimedOut(timeout));
+      }
+      std::thread::sleep(POLL_INTERVAL);
+    },
+  };
+  for reader in readers {
+    let _ = reader.join();
+  }
+  if !exit_status.success() {
+    return Err(univer_error!("executing command failed with status code: {}", exit_status));
+  }
+  Ok(Completion::Finished)
+}
+
+/// Kills the process together with all its descendants.
+///
+/// Descendants are found before anything is killed, killed processes would leave their children orphaned.
+fn kill_tree(child: &mut std::process::Child) {
+  #[cfg(unix)]
+  {
+    let mut pids = vec![child.id()];
+    if let Ok(output) = std::process::Command::new("ps").args(["-A", "-o", "pid=,ppid="]).output() {
+      let processes = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+          let mut ids = line.split_whitespace().map(|id| id.parse::<u32>().ok());
+          Some((ids.next()??, ids.next()??))
+        })
+        .collect::<Vec<(u32, u32)>>();
+      let mut index = 0;
+      while index < pids.len() {
+        let parent = pids[index];
+        pids.extend(processes.iter().filter(|(_, ppid)| *ppid == parent).map(|(pid, _)| *pid));
+        index += 1;
+      }
+    }
+    if pids.len() > 1 {
+      let _ = std::process::Command::new("kill").arg("-KILL").args(pids[1..].iter().map(|pid| pid.to_string())).output();
+    }
+  }
+  #[cfg(windows)]
+  {
+    let _ = std::process::Command::new("taskkill").args(["/F", "/T", "/PID", &child.id().to_string()]).output();
+  }
+  let _ = child.kill();
+  let _ = child.wait();
+}
+
+/// Copies lines read from the source to the sink and to the log file.
+fn tee(source: impl Read, mut sink: impl Write, log_file: &Mutex<File>) {
+  let escape_sequence = Regex::new("\x1b\\[[0-9;?]*[A-Za-z]").ok();
+  let mut reader = BufReader::new(source);
+  let mut line = vec![];
+  while reader.read_until(b'\n', &mut line).unwrap_or_default() > 0 {
+    let _ = sink.write_all(&line).and_then(|_| sink.flush());
+    let text = String::from_utf8_lossy(&line);
+    let text = match &escape_sequence {
+      Some(regex) => regex.replace_all(&text, "").into_owned(),
+      None => text.into_owned(),
+    };
+    if let Ok(mut log_file) = log_file.lock() {
+      let _ = log_file.write_all(text.as_bytes());
+    }
+    line.clear();
+  }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_timeout_kills_descendants() {
+    let log_path = std::env::temp_dir().join(format!("univer-timeout-{}.log", std::process::id()));
+    let started = Instant::now();
+    // The background process inherits the output, it would keep it open after the shell is killed.
+    let completion = execute_logged_command("sh", ["-c", "sleep 30 & wait"], ".", &[], &log_path, Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(Completion::TimedOut(Duration::from_secs(1)), completion);
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(std::fs::read_to_string(&log_path).unwrap().contains("command timed out after 1s and was killed"));
+    std::fs::remove_file(log_path).unwrap();
+  }
+}
@@ -0,0 +1,108 @@
+//! # Verification of packaged crate contents before publishing
+
+use crate::config::Config;
+use crate::errors::{Result, univer_error};
+use crate::model::Member;
+use regex::Regex;
+
+/// Result of verifying the contents of a packaged crate.
+#[derive(Debug, Default, Clone)]
+pub struct Verification {
+  /// Number of files included in the packaged crate.
+  pub files: usize,
+  /// Total size of files included in the packaged crate, in bytes.
+  pub size: u64,
+  /// Problems found in the packaged crate.
+  pub problems: Vec<String>,
+}
+
+impl Verification {
+  /// Returns the summary with the number of files and total size.
+  pub fn summary(&self) -> String {
+    format!("{} {}, {}", self.files, if self.files == 1 { "file" } else { "files" }, format_size(self.size))
+  }
+}
+
+/// Verifies the contents of the packaged crate.
+///
+/// Files included in the package are listed using `cargo package --list`.
+/// Files generated by cargo (like `Cargo.toml.orig`) are counted, but have no size.
+pub fn verify(member: &Member, config: &Config) -> Result<Verification> {
+  let mut verification = Verification::default();
+  // Check files referenced in the manifest, cargo refuses to package the crate when they are missing.
+  for (kind, path) in [("readme", &member.readme), ("license", &member.license_file)] {
+    if let Some(path) = path.as_ref().filter(|path| !path.exists()) {
+      verification
+        .problems
+        .push(format!("{} file '{}' not found", kind, path.strip_prefix(&member.manifest_dir).unwrap_or(path)));
+    }
+  }
+  if !verification.problems.is_empty() {
+    return Ok(verification);
+  }
+  let forbidden = config
+    .forbidden
+    .iter()
+    .map(|pattern| pattern_to_regex(pattern).map(|regex| (pattern, regex)))
+    .collect::<Result<Vec<_>>>()?;
+  for file in list_files(member)? {
+    verification.files += 1;
+    let size = member.manifest_dir.join(&file).metadata().map(|metadata| metadata.len()).unwrap_or_default();
+    verification.size += size;
+    if size > config.max_file_size {
+      verification
+        .problems
+        .push(format!("file '{}' is too large ({}, limit {})", file, format_size(size), format_size(config.max_file_size)));
+    }
+    // Patterns without a slash are matched against the file name, like in `.gitignore`.
+    let file_name = file.rsplit('/').next().unwrap_or(&file);
+    for (pattern, regex) in &forbidden {
+      if regex.is_match(if pattern.contains('/') { &file } else { file_name }) {
+        verification.problems.push(format!("file '{}' matches forbidden pattern '{}'", file, pattern));
+      }
+    }
+  }
+  Ok(verification)
+}
+
+/// Returns files included in the packaged crate, relative to the crate directory.
+fn list_files(member: &Member) -> Result<Vec<String>> {
+  let output = std::process::Command::new("cargo")
+    .args(["package", "--list", "--allow-dirty"])
+    .current_dir(&member.manifest_dir)
+    .output()
+    .map_err(|e| univer_error!("listing files of crate '{}' failed, reason: {}", member.name, e))?;
+  if !output.status.success() {
+    return Err(univer_error!(
+      "listing files of crate '{}' failed, reason: {}",
+      member.name,
+      String::from_utf8_lossy(&output.stderr).trim()
+    ));
+  }
+  Ok(
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(|line| line.trim().replace('\\', "/"))
+      .filter(|line| !line.is_empty())
+      .collect(),
+  )
+}
+
+/// Converts the glob pattern into regular expression, `*` and `?` do not match `/`, `**` matches any directories.
+fn pattern_to_regex(pattern: &str) -> Result<Regex> {
+  let expression = regex::escape(pattern)
+    .replace(r"\*\*/", "(.*/)?")
+    .replace(r"\*\*", ".*")
+    .replace(r"\*", "[^/]*")
+    .replace(r"\?", "[^/]");
+  Regex::new(&format!("^{}$", expression)).map_err(|e| univer_error!("invalid forbidden pattern '{}', reason: {}", pattern, e))
+}
+
+/// Formats the size in bytes for humans.
+fn format_size(size: u64) -> String {
+  match size {
+    0..1_024 => format!("{} B", size),
+    1_024..1_048_576 => format!("{:.1} KiB", size as f64 / 1_024.0),
+    _ => format!("{:.1} MiB", size as f64 / 1_048_576.0),
+  }
+}
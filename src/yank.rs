@@ -0,0 +1,121 @@
+//! # Yanking all crates of a workspace release
+
+use crate::config::Options;
+use crate::errors::*;
+use crate::model::{Member, Workspace};
+use crate::output::{self, auto, info, warning};
+use crate::utils;
+use antex::StyledText;
+use cargo_metadata::semver::Version;
+use std::path::Path;
+
+/// Yanks (or un-yanks when `undo` is `true`) the specified version of all workspace crates.
+///
+/// Crates are yanked in reverse publishing order, dependent crates first. Crates having a different
+/// version (independent versions mode) are skipped. Failures do not stop yanking remaining crates,
+/// results are reported for each crate.
+pub fn yank(manifest_dir: &Path, version: &str, undo: bool, dry_run: bool, accept_all: bool, options: &Options) -> Result<()> {
+  let version = Version::parse(version).map_err(|e| univer_error!("invalid version '{}', reason: {}", version, e))?;
+  let workspace = Workspace::load(manifest_dir, options)?;
+  let config = workspace.config();
+  let (mut members, skipped): (Vec<Member>, Vec<Member>) = utils::sort(workspace.members.clone(), config.sort)?
+    .into_iter()
+    .partition(|member| Version::parse(&member.version).is_ok_and(|member_version| member_version == version));
+  if members.is_empty() {
+    return Err(univer_error!("no crates with version '{}' to yank", version));
+  }
+  members.reverse();
+  let version = version.to_string();
+  let action = if undo { "Un-yank" } else { "Yank" };
+  info!();
+  info!("{} version: {}", action, auto().bold().green().s(&version).reset());
+  info!();
+  info!("{} crates:", action);
+  for member in &members {
+    info!(
+      "{}  {}  {}",
+      auto().bold().blue().s(&member.name).reset(),
+      auto().bold().green().s('v').s(&version).reset(),
+      member.path
+    );
+  }
+  if !skipped.is_empty() {
+    info!();
+    info!("Skipped crates, versions differ:");
+    for member in &skipped {
+      info!(
+        "{}  {}  {}",
+        auto().bold().blue().s(&member.name).reset(),
+        auto().bold().green().s('v').s(&member.version).reset(),
+        member.path
+      );
+    }
+  }
+  info!();
+  if !dry_run && !utils::prompt(&format!("Do you want to {} all these crates?", action.to_lowercase()), accept_all)? {
+    return Ok(());
+  }
+  let mut results = vec![];
+  for member in &members {
+    let label = if undo {
+      auto().bold().bg_green().s("  UN-YANK  ").reset()
+    } else {
+      auto().bold().bg_red().s("  YANK  ").reset()
+    };
+    info!(
+      "\n{} {} {} {}",
+      label,
+      auto().bold().blue().s(&member.name).reset(),
+      auto().bold().green().s('v').s(&version).reset(),
+      member.path
+    );
+    if !dry_run {
+      let result = utils::execute_command("cargo", yank_args(&member.name, &version, undo, config.registry.as_deref()), workspace.root(), &[]);
+      if let Err(reason) = &result {
+        warning!("{} of crate '{}' failed, reason: {}", action.to_lowercase(), member.name, reason);
+      }
+      results.push((member, result.is_ok()));
+    }
+  }
+  if results.is_empty() {
+    return Ok(());
+  }
+  info!();
+  info!("Results:");
+  for (member, success) in &results {
+    info!(
+      "{}  {}  {}",
+      auto().bold().blue().s(&member.name).reset(),
+      auto().bold().green().s('v').s(&version).reset(),
+      if *success { auto().green().s("ok").reset() } else { auto().red().s("failed").reset() }
+    );
+  }
+  let failed = results
+    .iter()
+    .filter(|(_, success)| !success)
+    .map(|(member, _)| member.name.as_str())
+    .collect::<Vec<&str>>();
+  if !failed.is_empty() {
+    return Err(univer_error!("{} failed for crates: {}", action.to_lowercase(), failed.join(", ")));
+  }
+  Ok(())
+}
+
+/// Returns arguments passed to `cargo yank` command.
+fn yank_args(name: &str, version: &str, undo: bool, registry: Option<&str>) -> Vec<String> {
+  let mut args = vec![
+    "yank".to_string(),
+    "--version".to_string(),
+    version.to_string(),
+    format!("--color={}", output::cargo_color()),
+  ];
+  if undo {
+    args.push("--undo".to_string());
+  }
+  if let Some(registry) = registry {
+    args.push("--registry".to_string());
+    args.push(registry.to_string());
+  }
+  args.push(name.to_string());
+  args
+}
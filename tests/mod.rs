@@ -1,6 +1,13 @@
+mod registry;
+mod test_bump;
+mod test_changed;
 mod test_cli;
 mod test_develop;
+mod test_graph;
+mod test_owners;
 mod test_publish;
+mod test_restore;
+mod test_yank;
 
 #[cfg(not(target_os = "windows"))]
 fn normalize(s: &str) -> String {
@@ -21,3 +28,67 @@ fn normalize_exe(s: &str) -> String {
 fn normalize_exe(s: &str) -> String {
   s.replace("||E||", ".exe")
 }
+
+/// Normalizes debug traces, fixture directories and timestamps of backups are replaced with placeholders.
+fn normalize_debug(s: &str) -> String {
+  let s = s.replace('\\', "/");
+  let s = regex::Regex::new(r"\S*/tests/test_\w+/test_\d+/").unwrap().replace_all(&s, "{{dir}}/");
+  regex::Regex::new(r"/\d+-(\w+)\.toml").unwrap().replace_all(&s, "/{{timestamp}}-$1.toml").to_string()
+}
+
+/// Normalizes summaries of packaged crates, numbers of files and sizes are replaced with placeholders.
+fn normalize_package(s: &str) -> String {
+  regex::Regex::new(r"\d+ files?, [\d.]+ \w+")
+    .unwrap()
+    .replace_all(s, "{{files}} files, {{size}}")
+    .to_string()
+}
+
+/// Copies the fixture directory recursively, skipping build outputs, lock files and the test source.
+///
+/// Only the `mod.rs` file at the top level is the test source, nested ones belong to fixture crates.
+fn copy_dir(from: &std::path::Path, to: &std::path::Path) {
+  copy_dir_entries(from, to, true);
+}
+
+/// Copies entries of the directory recursively, the test source is skipped at the top level only.
+fn copy_dir_entries(from: &std::path::Path, to: &std::path::Path, top_level: bool) {
+  std::fs::create_dir_all(to).unwrap();
+  for entry in std::fs::read_dir(from).unwrap().flatten() {
+    let name = entry.file_name();
+    if ["target", "Cargo.lock"].iter().any(|skipped| name == *skipped) || (top_level && name == "mod.rs") {
+      continue;
+    }
+    if entry.path().is_dir() {
+      copy_dir_entries(&entry.path(), &to.join(&name), false);
+    } else {
+      std::fs::copy(entry.path(), to.join(&name)).unwrap();
+    }
+  }
+}
+
+/// Executes the git command in the directory, with the test identity.
+fn git(dir: &std::path::Path, args: &[&str]) {
+  let status = std::process::Command::new("git")
+    .args(["-c", "user.name=univer", "-c", "user.email=univer@localhost"])
+    .args(args)
+    .current_dir(dir)
+    .stdout(std::process::Stdio::null())
+    .status()
+    .unwrap();
+  assert!(status.success(), "git {} failed", args.join(" "));
+}
+
+/// Copies the fixture workspace into a new git repository in the temporary directory,
+/// with all files committed, and returns the path of the repository.
+fn git_workspace(fixture_dir: &std::path::Path, name: &str) -> std::path::PathBuf {
+  let dir = std::env::temp_dir().join(format!("univer-{}-{}", name, std::process::id()));
+  if dir.exists() {
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+  copy_dir(fixture_dir, &dir);
+  git(&dir, &["init", "--quiet"]);
+  git(&dir, &["add", "--all"]);
+  git(&dir, &["commit", "--quiet", "--message", "initial"]);
+  dir
+}
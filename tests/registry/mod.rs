@@ -0,0 +1,369 @@
+//! # Local registry for end-to-end publishing tests
+//!
+//! The registry serves the sparse index, downloads and the publish API over HTTP
+//! from a temporary directory. Cargo is pointed at it using environment variables,
+//! with isolated cargo home, so published crates never leave the machine.
+//! Fixture dependencies refer to the registry with `registry = "local"`.
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Name of the local registry in cargo configuration.
+pub const REGISTRY_NAME: &str = "local";
+
+/// Token accepted by the local registry.
+const TOKEN: &str = "univer-test-token";
+
+/// Login of the user owning the token, the initial owner of published crates.
+pub const LOGIN: &str = "univer";
+
+/// Number of seconds the hanging publish request is left without response.
+const HANG_SECONDS: u64 = 30;
+
+/// Counter making names of registry directories unique within the test process.
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Local registry serving crates from a temporary directory.
+pub struct Registry {
+  /// Root directory of the registry, with index, crates and isolated cargo home.
+  root: PathBuf,
+  /// Port the registry server listens on.
+  port: u16,
+}
+
+impl Registry {
+  /// Starts the registry server on a free local port.
+  pub fn start() -> Self {
+    let root = std::env::temp_dir().join(format!("univer-registry-{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::SeqCst)));
+    if root.exists() {
+      std::fs::remove_dir_all(&root).unwrap();
+    }
+    std::fs::create_dir_all(root.join("index")).unwrap();
+    std::fs::create_dir_all(root.join("crates")).unwrap();
+    std::fs::create_dir_all(root.join("cargo-home")).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server_root = root.clone();
+    std::thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        let root = server_root.clone();
+        std::thread::spawn(move || handle(stream, &root, port));
+      }
+    });
+    Self { root, port }
+  }
+
+  /// Returns environment variables pointing cargo at the local registry.
+  pub fn envs(&self) -> Vec<(String, String)> {
+    let name = REGISTRY_NAME.to_uppercase();
+    vec![
+      ("CARGO_HOME".to_string(), self.root.join("cargo-home").to_string_lossy().to_string()),
+      (format!("CARGO_REGISTRIES_{}_INDEX", name), format!("sparse+http://127.0.0.1:{}/index/", self.port)),
+      (format!("CARGO_REGISTRIES_{}_TOKEN", name), TOKEN.to_string()),
+      ("CARGO_TERM_COLOR".to_string(), "never".to_string()),
+    ]
+  }
+
+  /// Copies the fixture workspace into the registry directory and returns the path of the copy.
+  ///
+  /// The copy is outside the repository, so cargo does not complain about uncommitted changes.
+  pub fn workspace(&self, fixture_dir: impl AsRef<Path>) -> PathBuf {
+    let workspace_dir = self.root.join("workspace");
+    crate::copy_dir(fixture_dir.as_ref(), &workspace_dir);
+    workspace_dir
+  }
+
+  /// Runs univer with specified arguments in the working directory, using the local registry.
+  pub fn univer(&self, dir: impl AsRef<Path>, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_univer")).args(args).envs(self.envs()).current_dir(dir).output().unwrap()
+  }
+
+  /// Runs univer like [Registry::univer], answering prompts with lines of the input.
+  #[cfg(not(target_os = "windows"))]
+  pub fn univer_with_input(&self, dir: impl AsRef<Path>, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_univer"))
+      .args(args)
+      .envs(self.envs())
+      .current_dir(dir)
+      .stdin(std::process::Stdio::piped())
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+  }
+
+  /// Returns entries of the crate in the index, one per published version.
+  pub fn index(&self, name: &str) -> Vec<Value> {
+    std::fs::read_to_string(self.root.join("index").join(index_path(name)))
+      .map(|content| content.lines().map(|line| serde_json::from_str(line).unwrap()).collect())
+      .unwrap_or_default()
+  }
+
+  /// Returns names and versions of published crates, in the order of publishing.
+  pub fn published(&self) -> Vec<String> {
+    std::fs::read_to_string(self.root.join("published.txt"))
+      .map(|content| content.lines().map(|line| line.to_string()).collect())
+      .unwrap_or_default()
+  }
+
+  /// Returns logins of owners of the crate.
+  pub fn owners(&self, name: &str) -> Vec<String> {
+    read_owners(&self.root, name)
+  }
+
+  /// Replaces owners of the crate.
+  pub fn set_owners(&self, name: &str, owners: &[&str]) {
+    write_owners(&self.root, name, &owners.iter().map(|owner| owner.to_string()).collect::<Vec<String>>());
+  }
+
+  /// Makes the next publish requests fail, one response per request, in the specified order.
+  ///
+  /// Each response is an HTTP status like `429 Too Many Requests`, `hang` for no response at all,
+  /// or `hang after publish` for no response to the request that published the crate.
+  pub fn fail_publish(&self, responses: &[&str]) {
+    std::fs::write(
+      self.root.join("fail-publish.txt"),
+      responses.iter().map(|response| format!("{}\n", response)).collect::<String>(),
+    )
+    .unwrap();
+  }
+
+  /// Returns yank operations in the order of execution, like `yank alpha 0.1.0` or `unyank alpha 0.1.0`.
+  pub fn yanked(&self) -> Vec<String> {
+    std::fs::read_to_string(self.root.join("yanked.txt"))
+      .map(|content| content.lines().map(|line| line.to_string()).collect())
+      .unwrap_or_default()
+  }
+}
+
+impl Drop for Registry {
+  /// Removes the registry directory, the server thread ends with the test process.
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.root);
+  }
+}
+
+/// Handles a single HTTP request.
+fn handle(mut stream: TcpStream, root: &Path, port: u16) {
+  let mut reader = BufReader::new(stream.try_clone().unwrap());
+  let mut request_line = String::new();
+  if reader.read_line(&mut request_line).is_err() {
+    return;
+  }
+  let mut parts = request_line.split_whitespace();
+  let (method, path) = (parts.next().unwrap_or_default().to_string(), parts.next().unwrap_or_default().to_string());
+  let mut content_length = 0;
+  let mut authorized = false;
+  loop {
+    let mut line = String::new();
+    if reader.read_line(&mut line).unwrap_or_default() == 0 || line.trim().is_empty() {
+      break;
+    }
+    let (key, value) = line.split_once(':').unwrap_or_default();
+    match key.trim().to_lowercase().as_str() {
+      "content-length" => content_length = value.trim().parse().unwrap_or_default(),
+      "authorization" => authorized = value.trim() == TOKEN,
+      "expect" => stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap(),
+      _ => {}
+    }
+  }
+  let mut body = vec![0; content_length];
+  reader.read_exact(&mut body).unwrap();
+  let (status, content) = match (method.as_str(), path.as_str()) {
+    ("GET", "/index/config.json") => (
+      "200 OK",
+      json!({ "dl": format!("http://127.0.0.1:{}/crates/{{crate}}-{{version}}.crate", port), "api": format!("http://127.0.0.1:{}", port) })
+        .to_string()
+        .into_bytes(),
+    ),
+    (method, path) if path.starts_with("/api/v1/crates/") && path.ends_with("/owners") => {
+      let name = path.trim_start_matches("/api/v1/crates/").trim_end_matches("/owners");
+      let mut owners = read_owners(root, name);
+      let users = serde_json::from_slice::<Value>(&body).map(|body| body["users"].clone()).unwrap_or_default();
+      let users = users.as_array().into_iter().flatten().filter_map(|user| user.as_str()).map(|user| user.to_string());
+      match method {
+        "GET" => {}
+        "PUT" if authorized => {
+          for user in users {
+            if !owners.contains(&user) {
+              owners.push(user);
+            }
+          }
+        }
+        "DELETE" if authorized => {
+          let users = users.collect::<Vec<String>>();
+          owners.retain(|owner| !users.contains(owner));
+        }
+        _ => return respond(&mut stream, "403 Forbidden", json!({ "errors": [{ "detail": "invalid token" }] }).to_string().into_bytes()),
+      }
+      write_owners(root, name, &owners);
+      let users = owners
+        .iter()
+        .enumerate()
+        .map(|(id, login)| json!({ "id": id + 1, "login": login, "name": null }))
+        .collect::<Vec<Value>>();
+      ("200 OK", json!({ "ok": true, "msg": "owners changed", "users": users }).to_string().into_bytes())
+    }
+    ("GET", path) => match std::fs::read(root.join(path.trim_start_matches('/'))) {
+      Ok(content) => ("200 OK", content),
+      Err(_) => ("404 Not Found", vec![]),
+    },
+    ("PUT", "/api/v1/crates/new") if authorized => {
+      if let Some(response) = next_failure(root) {
+        if response == "hang after publish" {
+          publish(root, &body);
+        }
+        if response.starts_with("hang") {
+          std::thread::sleep(std::time::Duration::from_secs(HANG_SECONDS));
+          return;
+        }
+        return respond(&mut stream, &response, json!({ "errors": [{ "detail": response }] }).to_string().into_bytes());
+      }
+      publish(root, &body);
+      (
+        "200 OK",
+        json!({ "warnings": { "invalid_categories": [], "invalid_badges": [], "other": [] } })
+          .to_string()
+          .into_bytes(),
+      )
+    }
+    ("DELETE" | "PUT", path) if authorized && (path.ends_with("/yank") || path.ends_with("/unyank")) => {
+      let segments = path.split('/').collect::<Vec<&str>>();
+      match segments.as_slice() {
+        ["", "api", "v1", "crates", name, version, operation] if set_yanked(root, name, version, *operation == "yank") => {
+          ("200 OK", json!({ "ok": true }).to_string().into_bytes())
+        }
+        _ => ("404 Not Found", json!({ "errors": [{ "detail": "crate not found" }] }).to_string().into_bytes()),
+      }
+    }
+    ("DELETE" | "PUT", _) => ("403 Forbidden", json!({ "errors": [{ "detail": "invalid token" }] }).to_string().into_bytes()),
+    _ => ("405 Method Not Allowed", vec![]),
+  };
+  respond(&mut stream, status, content);
+}
+
+/// Takes the next planned failure of the publish request, if any.
+fn next_failure(root: &Path) -> Option<String> {
+  let path = root.join("fail-publish.txt");
+  let content = std::fs::read_to_string(&path).ok()?;
+  let mut lines = content.lines();
+  let response = lines.next()?.to_string();
+  std::fs::write(&path, lines.map(|line| format!("{}\n", line)).collect::<String>()).unwrap();
+  Some(response)
+}
+
+/// Writes the response with specified status and content.
+fn respond(stream: &mut TcpStream, status: &str, content: Vec<u8>) {
+  let _ = stream.write_all(format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, content.len()).as_bytes());
+  let _ = stream.write_all(&content);
+}
+
+/// Stores the published crate and adds its entry to the index.
+///
+/// The body consists of the metadata length, JSON metadata, the crate file length and the crate file.
+fn publish(root: &Path, body: &[u8]) {
+  let length = |offset: usize| u32::from_le_bytes(body[offset..offset + 4].try_into().unwrap()) as usize;
+  let metadata_length = length(0);
+  let metadata: Value = serde_json::from_slice(&body[4..4 + metadata_length]).unwrap();
+  let crate_length = length(4 + metadata_length);
+  let crate_file = &body[8 + metadata_length..8 + metadata_length + crate_length];
+  let name = metadata["name"].as_str().unwrap();
+  let version = metadata["vers"].as_str().unwrap();
+  std::fs::write(root.join("crates").join(format!("{}-{}.crate", name, version)), crate_file).unwrap();
+  let deps = metadata["deps"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|dep| {
+      // Renamed dependencies are indexed by the name used in the manifest.
+      let (name, package) = match dep["explicit_name_in_toml"].as_str() {
+        Some(explicit_name) => (json!(explicit_name), dep["name"].clone()),
+        None => (dep["name"].clone(), Value::Null),
+      };
+      json!({
+        "name": name,
+        "req": dep["version_req"],
+        "features": dep["features"],
+        "optional": dep["optional"],
+        "default_features": dep["default_features"],
+        "target": dep["target"],
+        "kind": dep["kind"],
+        "registry": dep["registry"],
+        "package": package,
+      })
+    })
+    .collect::<Vec<Value>>();
+  let cksum = Sha256::digest(crate_file).iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+  let entry = json!({
+    "name": name,
+    "vers": version,
+    "deps": deps,
+    "cksum": cksum,
+    "features": metadata["features"],
+    "yanked": false,
+  });
+  let index_file = root.join("index").join(index_path(name));
+  std::fs::create_dir_all(index_file.parent().unwrap()).unwrap();
+  let mut file = std::fs::OpenOptions::new().create(true).append(true).open(index_file).unwrap();
+  writeln!(file, "{}", entry).unwrap();
+  let mut file = std::fs::OpenOptions::new().create(true).append(true).open(root.join("published.txt")).unwrap();
+  writeln!(file, "{} {}", name, version).unwrap();
+  if read_owners(root, name).is_empty() {
+    write_owners(root, name, &[LOGIN.to_string()]);
+  }
+}
+
+/// Returns logins of owners of the crate, in the order of adding.
+fn read_owners(root: &Path, name: &str) -> Vec<String> {
+  std::fs::read_to_string(root.join("owners").join(name))
+    .map(|content| content.lines().map(|line| line.to_string()).collect())
+    .unwrap_or_default()
+}
+
+/// Saves logins of owners of the crate.
+fn write_owners(root: &Path, name: &str, owners: &[String]) {
+  std::fs::create_dir_all(root.join("owners")).unwrap();
+  std::fs::write(root.join("owners").join(name), owners.iter().map(|owner| format!("{}\n", owner)).collect::<String>()).unwrap();
+}
+
+/// Marks the published version of the crate as yanked (or not yanked), returns `false` when not published.
+fn set_yanked(root: &Path, name: &str, version: &str, yanked: bool) -> bool {
+  let index_file = root.join("index").join(index_path(name));
+  let Ok(content) = std::fs::read_to_string(&index_file) else {
+    return false;
+  };
+  let mut found = false;
+  let mut entries = vec![];
+  for line in content.lines() {
+    let mut entry: Value = serde_json::from_str(line).unwrap();
+    if entry["vers"] == version {
+      entry["yanked"] = json!(yanked);
+      found = true;
+    }
+    entries.push(format!("{}\n", entry));
+  }
+  std::fs::write(index_file, entries.concat()).unwrap();
+  if found {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(root.join("yanked.txt")).unwrap();
+    writeln!(file, "{} {} {}", if yanked { "yank" } else { "unyank" }, name, version).unwrap();
+  }
+  found
+}
+
+/// Returns the path of the index file for the crate, relative to the index root.
+fn index_path(name: &str) -> PathBuf {
+  let name = name.to_lowercase();
+  match name.len() {
+    1 => Path::new("1").join(&name),
+    2 => Path::new("2").join(&name),
+    3 => Path::new("3").join(&name[..1]).join(&name),
+    _ => Path::new(&name[..2]).join(&name[2..4]).join(&name),
+  }
+}
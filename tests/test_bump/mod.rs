@@ -0,0 +1,6 @@
+use super::*;
+
+mod test_01;
+mod test_02;
+mod test_03;
+mod test_04;
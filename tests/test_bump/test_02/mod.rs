@@ -0,0 +1,57 @@
+/// This test verifies that the pre-release counter is incremented within the same channel.
+#[test]
+fn _0001() {
+  cli_assert::command!()
+    .code(0)
+    .arg("bump")
+    .arg("pre")
+    .arg("--channel")
+    .arg("beta")
+    .arg("--dry-run")
+    .stdout("\nBump version: 3.1.0-beta.2 -> 3.1.0-beta.3\n\n")
+    .stderr("")
+    .execute();
+}
+
+/// This test verifies that switching to the next channel starts counting from 1.
+#[test]
+fn _0002() {
+  cli_assert::command!()
+    .code(0)
+    .arg("bump")
+    .arg("pre")
+    .arg("--channel")
+    .arg("rc")
+    .arg("--dry-run")
+    .stdout("\nBump version: 3.1.0-beta.2 -> 3.1.0-rc.1\n\n")
+    .stderr("")
+    .execute();
+}
+
+/// This test verifies that switching to the preceding channel is reported as an error.
+#[test]
+fn _0003() {
+  cli_assert::command!()
+    .code(1)
+    .arg("bump")
+    .arg("pre")
+    .arg("--channel")
+    .arg("alpha")
+    .arg("--dry-run")
+    .stdout("")
+    .stderr("error: version '3.1.0-alpha.1' is not greater than '3.1.0-beta.2'\n")
+    .execute();
+}
+
+/// This test verifies that the final release strips the pre-release.
+#[test]
+fn _0004() {
+  cli_assert::command!()
+    .code(0)
+    .arg("bump")
+    .arg("release")
+    .arg("--dry-run")
+    .stdout("\nBump version: 3.1.0-beta.2 -> 3.1.0\n\n")
+    .stderr("")
+    .execute();
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+"version" = "0.1.1-rc.1"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+beta = { path = "packages/beta" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Bump version: 0.1.0 -> 0.1.1-rc.1
+
+"#;
+
+/// This test verifies that the version is bumped when its key is quoted.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Bump the workspace version.
+  cli_assert::command!()
+    .code(0)
+    .arg("bump")
+    .arg("pre")
+    .arg("--channel")
+    .arg("rc")
+    .arg("--accept-all")
+    .arg("--no-update-lock")
+    .stdout(EXPECTED_STDOUT)
+    .stderr("")
+    .execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
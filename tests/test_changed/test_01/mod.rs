@@ -0,0 +1,142 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_NOT_RELEASED: &str = r#"
+Changed crates:
+alpha  packages/alpha  not released yet
+beta  packages/beta  not released yet
+gamma  packages/gamma  not released yet
+"#;
+
+const EXPECTED_CHANGED: &str = r#"
+Changed crates:
+alpha  packages/alpha  changed since v0.1.0
+beta  packages/beta  depends on changed 'alpha'
+
+Unchanged crates:
+gamma  packages/gamma
+"#;
+
+/// This test verifies that changes since the previous release tag are propagated to dependent crates.
+#[test]
+fn _0001() {
+  let dir = git_workspace(Path::new(file!()).parent().unwrap(), "changed");
+  // Without release tags all crates are changed.
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout(EXPECTED_NOT_RELEASED)
+    .stderr("")
+    .execute();
+  // Nothing changed since the release.
+  git(&dir, &["tag", "v0.1.0"]);
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout("\nNo changed crates\n")
+    .stderr("")
+    .execute();
+  // The changed crate and crates depending on it need a release.
+  std::fs::write(dir.join("packages/alpha/src/lib.rs"), "pub fn alpha() -> u32 {\n  2\n}\n").unwrap();
+  git(&dir, &["commit", "--quiet", "--all", "--message", "change"]);
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout(EXPECTED_CHANGED)
+    .stderr("")
+    .execute();
+  std::fs::remove_dir_all(dir).unwrap();
+}
+
+const EXPECTED_UNTRACKED: &str = r#"
+Changed crates:
+gamma  packages/gamma  changed since v0.1.0
+
+Unchanged crates:
+alpha  packages/alpha
+beta  packages/beta
+"#;
+
+const EXPECTED_EDITION_CHANGED: &str = r#"
+Changed crates:
+gamma  packages/gamma  changed since v0.1.0
+
+Unchanged crates:
+alpha  packages/alpha
+beta  packages/beta
+"#;
+
+const EXPECTED_DEPENDENCY_CHANGED: &str = r#"
+Changed crates:
+beta  packages/beta  changed since v0.1.0
+gamma  packages/gamma  changed since v0.1.0
+
+Unchanged crates:
+alpha  packages/alpha
+"#;
+
+/// This test verifies that untracked files of crates are detected, and that only the parts
+/// of the workspace manifest inherited by crates are compared.
+#[test]
+fn _0002() {
+  let dir = git_workspace(Path::new(file!()).parent().unwrap(), "changed-untracked");
+  git(&dir, &["tag", "v0.1.0"]);
+  // The untracked file changes only the crate it belongs to.
+  std::fs::write(dir.join("packages/gamma/src/extra.rs"), "pub fn extra() {}\n").unwrap();
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout(EXPECTED_UNTRACKED)
+    .stderr("")
+    .execute();
+  std::fs::remove_file(dir.join("packages/gamma/src/extra.rs")).unwrap();
+  // Profiles, the version bump and switching from paths to versions change no crate.
+  let manifest_path = dir.join("Cargo.toml");
+  let manifest = std::fs::read_to_string(&manifest_path)
+    .unwrap()
+    .replace("version = \"0.1.0\"", "version = \"0.2.0\"")
+    .replace("alpha = { path = \"packages/alpha\" }", "alpha = { version = \"0.2.0\" }");
+  std::fs::write(&manifest_path, format!("{}\n[profile.release]\nlto = true\n", manifest)).unwrap();
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout("\nNo changed crates\n")
+    .stderr("")
+    .execute();
+  // The changed inherited package key changes only the crate inheriting it.
+  let manifest = std::fs::read_to_string(&manifest_path).unwrap().replace("edition = \"2021\"", "edition = \"2018\"");
+  std::fs::write(&manifest_path, manifest).unwrap();
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout(EXPECTED_EDITION_CHANGED)
+    .stderr("")
+    .execute();
+  // The changed workspace dependency changes only the crate referring to it.
+  let manifest = std::fs::read_to_string(&manifest_path)
+    .unwrap()
+    .replace("alpha = { version = \"0.2.0\" }", "alpha = { version = \"0.2.0\", default-features = false }");
+  std::fs::write(&manifest_path, manifest).unwrap();
+  cli_assert::command!()
+    .code(0)
+    .arg("changed")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .stdout(EXPECTED_DEPENDENCY_CHANGED)
+    .stderr("")
+    .execute();
+  std::fs::remove_dir_all(dir).unwrap();
+}
@@ -2,16 +2,25 @@ use super::*;
 
 const EXPECTED: &str = r#"Unified versions publisher
 
-Usage: univer||E|| [COMMAND]
+Usage: univer||E|| [OPTIONS] [COMMAND]
 
 Commands:
   publish  Publish workspace crates
   develop  Switch workspace crates to local development mode
+  graph    Export the dependency graph of workspace crates
+  restore  Restore workspace manifest from backup
+  yank     Yank a version of workspace crates
+  owners   Manage owners of workspace crates
+  bump     Bump the workspace version
+  changed  List crates changed since the previous release
   help     Print this message or the help of the given subcommand(s)
 
 Options:
-  -h, --help     Print help
-  -V, --version  Print version
+  -q, --quiet         Report only warnings, errors and questions
+  -v, --verbose       Report details, like the effective configuration and debug traces
+      --color <WHEN>  Coloring of the output, overrides CARGO_TERM_COLOR and NO_COLOR [possible values: auto, always, never]
+  -h, --help          Print help
+  -V, --version       Print version
 "#;
 
 #[test]
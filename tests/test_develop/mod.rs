@@ -1,3 +1,11 @@
 use super::*;
 
 mod test_01;
+mod test_02;
+mod test_03;
+mod test_04;
+mod test_05;
+mod test_06;
+mod test_07;
+mod test_08;
+mod test_09;
@@ -38,7 +38,7 @@ fn _0001() {
   let backup = working_dir.join(Path::new("Cargo.toml.bak"));
   std::fs::copy(&original, &backup).unwrap();
   // Replace version numbers with local paths.
-  cli_assert::command!().code(0).arg("develop").stdout("").stderr("").execute();
+  cli_assert::command!().code(0).arg("develop").arg("--no-update-lock").stdout("").stderr("").execute();
   // Make sure the Cargo.toml file is modified properly.
   assert_eq!(normalize(EXPECTED), std::fs::read_to_string(&original).unwrap());
   // Revert changes to Cargo.toml file.
@@ -0,0 +1,37 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0" }
+beta = { version = "0.1.0" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+  PRE-DEVELOP   alpha cargo univer-missing-hook
+"#;
+
+/// This test verifies that failing hook aborts switching to development mode.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Try to replace version numbers with local paths.
+  cli_assert::command!().code(1).arg("develop").stdout(EXPECTED_STDOUT).execute();
+  // Make sure the Cargo.toml file is not modified.
+  assert_eq!(normalize(EXPECTED), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+helper = { path = "packages/helper" }
+"#;
+
+/// This test verifies switching to development mode when a crate declares its own version.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Replace version numbers with local paths.
+  cli_assert::command!().code(0).arg("develop").arg("--independent-versions").execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
+
+/// This test verifies that a crate declaring its own version is rejected without independent versions mode.
+#[test]
+fn _0002() {
+  cli_assert::command!()
+    .code(1)
+    .arg("develop")
+    .stderr("error: missing [package].version.workspace attribute in manifest for dependency 'helper'\n")
+    .execute();
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+beta = { path = "packages/beta" }
+gamma = { path = "packages/gamma" }
+delta = { path = "packages/delta" }
+"#;
+
+/// This test verifies switching to development mode when versions have various requirement operators.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Replace version numbers with local paths.
+  cli_assert::command!().code(0).arg("develop").arg("--no-update-lock").stderr("").execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
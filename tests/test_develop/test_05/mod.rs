@@ -0,0 +1,47 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_DEVELOP: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+beta = { path = "packages/beta" }
+gamma = { path = "packages/gamma" }
+
+[workspace.metadata.univer.req-style]
+"*" = "~"
+"#;
+
+/// This test verifies that requirement operators replaced in development mode are restored when publishing.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  let journal = working_dir.join(Path::new(".univer/journal.toml"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Replace version numbers with local paths.
+  cli_assert::command!().code(0).arg("develop").arg("--no-update-lock").stderr("").execute();
+  // Make sure the Cargo.toml file is modified properly and requirement operators are remembered.
+  assert_eq!(normalize(EXPECTED_DEVELOP), std::fs::read_to_string(&original).unwrap());
+  assert_eq!("[requirements]\nalpha = \"^\"\nbeta = \"=\"\ngamma = \"\"\n", std::fs::read_to_string(&journal).unwrap());
+  // Make sure the journal directory is ignored by git.
+  assert_eq!("*\n", std::fs::read_to_string(journal.parent().unwrap().join(".gitignore")).unwrap());
+  // Publish workspace crates, remembered requirement operators take precedence over the configured ones.
+  cli_assert::command!().code(0).arg("publish").arg("--dry-run").stderr("").execute();
+  // Make sure the original Cargo.toml file is restored and the journal is kept for the real publishing.
+  assert_eq!(std::fs::read_to_string(&backup).unwrap(), std::fs::read_to_string(&original).unwrap());
+  assert_eq!("[requirements]\nalpha = \"^\"\nbeta = \"=\"\ngamma = \"\"\n", std::fs::read_to_string(&journal).unwrap());
+  // Revert changes to Cargo.toml file and remove the journal.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap();
+  std::fs::remove_dir_all(journal.parent().unwrap()).unwrap()
+}
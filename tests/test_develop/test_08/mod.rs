@@ -0,0 +1,46 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+beta = { path = "packages/beta" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Cargo.lock  no changes
+"#;
+
+/// This test verifies updating the lock file after switching to development mode.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Make sure the lock file is up to date, it is verified before the manifest is changed.
+  let status = std::process::Command::new("cargo").arg("generate-lockfile").current_dir(working_dir).status().unwrap();
+  assert!(status.success());
+  // Replace version numbers with local paths and update the lock file.
+  cli_assert::command!()
+    .code(0)
+    .arg("develop")
+    .arg("--update-lock")
+    .arg("--locked")
+    .stdout(EXPECTED_STDOUT)
+    .execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,16 @@
+use std::path::Path;
+
+/// This test verifies that unsupported formats of dependency entries are reported instead of being skipped.
+#[test]
+fn _0001() {
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap();
+  cli_assert::command!()
+    .code(1)
+    .arg("develop")
+    .stdout("")
+    .stderr("error: unsupported format of dependency 'alpha' in [workspace.dependencies] table, expected an inline table on a single line: alpha = { path = \"packages/alpha\" }\n")
+    .execute();
+  // Make sure the workspace manifest is not modified.
+  assert_eq!(original, std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap());
+}
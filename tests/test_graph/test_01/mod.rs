@@ -0,0 +1,65 @@
+const EXPECTED_TEXT: &str = r#"delta
+├── alpha
+├── alpha [build]
+└── beta
+    └── alpha
+"#;
+
+const EXPECTED_DOT: &str = r#"digraph workspace {
+  node [shape=box];
+  "alpha" [label="alpha #1"];
+  "beta" [label="beta #2"];
+  "delta" [label="delta #3"];
+  "gamma" [label="gamma (publish = false)", style=dashed, color=gray];
+  "beta" -> "alpha" [label="normal"];
+  "delta" -> "alpha" [label="normal"];
+  "delta" -> "alpha" [label="build"];
+  "delta" -> "beta" [label="normal"];
+  "gamma" -> "delta" [label="dev"];
+}
+"#;
+
+const EXPECTED_MERMAID: &str = r#"graph TD
+  n0["alpha"]
+  n1["beta"]
+  n1 -->|normal| n0
+  classDef start stroke-width:3px
+  class n1 start
+"#;
+
+/// This test verifies exporting the dependency graph as plain text tree.
+#[test]
+fn _0001() {
+  cli_assert::command!().code(0).arg("graph").stdout(EXPECTED_TEXT).stderr("").execute();
+}
+
+/// This test verifies exporting the dependency graph in DOT format with publishing order and unpublished crates.
+#[test]
+fn _0002() {
+  cli_assert::command!()
+    .code(0)
+    .arg("graph")
+    .arg("--format")
+    .arg("dot")
+    .arg("--order")
+    .arg("--unpublished")
+    .stdout(EXPECTED_DOT)
+    .stderr("")
+    .execute();
+}
+
+/// This test verifies exporting the subgraph reachable from a crate in Mermaid format.
+#[test]
+fn _0003() {
+  cli_assert::command!()
+    .code(0)
+    .arg("graph")
+    .arg("--format")
+    .arg("mermaid")
+    .arg("--unpublished")
+    .arg("--from")
+    .arg("beta")
+    .stdout(EXPECTED_MERMAID)
+    .stderr("")
+    .execute();
+}
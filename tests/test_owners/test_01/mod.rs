@@ -0,0 +1,44 @@
+use crate::registry::{LOGIN, REGISTRY_NAME, Registry};
+use std::path::Path;
+
+const EXPECTED_LIST: &str = r#"
+       alice  univer
+alpha  x      x
+beta   -      x
+
+Inconsistent owners:
+alice  missing in: beta
+"#;
+
+const EXPECTED_CONSISTENT_LIST: &str = r#"
+       alice  univer
+alpha  x      x
+beta   x      x
+"#;
+
+/// This test verifies listing owners of all crates, adding and removing owners.
+#[test]
+fn _0001() {
+  // Publish workspace crates to the local registry, from the copy of the workspace.
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  // List owners, one of them owns only one crate.
+  registry.set_owners("alpha", &[LOGIN, "alice"]);
+  let output = registry.univer(&working_dir, &["owners", "list", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(EXPECTED_LIST, String::from_utf8_lossy(&output.stdout));
+  // Add the owner to all crates.
+  let output = registry.univer(&working_dir, &["owners", "add", "alice", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec![LOGIN, "alice"], registry.owners("alpha"));
+  assert_eq!(vec![LOGIN, "alice"], registry.owners("beta"));
+  let output = registry.univer(&working_dir, &["owners", "list", "--registry", REGISTRY_NAME]);
+  assert_eq!(EXPECTED_CONSISTENT_LIST, String::from_utf8_lossy(&output.stdout));
+  // Remove the owner from all crates.
+  let output = registry.univer(&working_dir, &["owners", "remove", LOGIN, "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec!["alice"], registry.owners("alpha"));
+  assert_eq!(vec!["alice"], registry.owners("beta"));
+}
@@ -1,3 +1,33 @@
 use super::*;
 
 mod test_01;
+mod test_02;
+mod test_03;
+mod test_04;
+mod test_05;
+mod test_06;
+mod test_07;
+mod test_08;
+mod test_09;
+mod test_10;
+mod test_11;
+mod test_12;
+mod test_13;
+mod test_14;
+mod test_15;
+mod test_16;
+mod test_17;
+mod test_18;
+mod test_19;
+mod test_20;
+mod test_21;
+mod test_22;
+mod test_23;
+// Hooks are shell scripts.
+#[cfg(not(target_os = "windows"))]
+mod test_24;
+mod test_25;
+mod test_26;
+mod test_27;
+mod test_28;
+mod test_29;
@@ -1,5 +1,6 @@
 use super::*;
 
+use regex::Regex;
 use std::path::Path;
 
 const EXPECTED_FILE: &str = r#"[workspace]
@@ -33,55 +34,76 @@ const EXPECTED_STDOUT: &str = r#"
 Publish version: 3.0.2
 
 Publish crates:
-cw-schema-derive  v3.0.2  packages/cw-schema-derive
-cw-schema  v3.0.2  packages/cw-schema
-cosmwasm-vm-derive  v3.0.2  packages/vm-derive
-cosmwasm-schema-derive  v3.0.2  packages/schema-derive
-cosmwasm-schema  v3.0.2  packages/schema
-cosmwasm-derive  v3.0.2  packages/derive
-cosmwasm-core  v3.0.2  packages/core
-cosmwasm-crypto  v3.0.2  packages/crypto
-cosmwasm-std  v3.0.2  packages/std
-cosmwasm-vm  v3.0.2  packages/vm
-cosmwasm-check  v3.0.2  packages/check
+cosmwasm-core  v3.0.2  packages/core  (level 0)
+cosmwasm-crypto  v3.0.2  packages/crypto  (level 1)
+cosmwasm-derive  v3.0.2  packages/derive  (level 0)
+cosmwasm-schema-derive  v3.0.2  packages/schema-derive  (level 0)
+cosmwasm-vm-derive  v3.0.2  packages/vm-derive  (level 0)
+cw-schema-derive  v3.0.2  packages/cw-schema-derive  (level 0)
+cw-schema  v3.0.2  packages/cw-schema  (level 1)
+cosmwasm-schema  v3.0.2  packages/schema  (level 2)
+cosmwasm-std  v3.0.2  packages/std  (level 3)
+cosmwasm-vm  v3.0.2  packages/vm  (level 4)
+cosmwasm-check  v3.0.2  packages/check  (level 5)
 
 
-  DRY-RUN   cw-schema-derive v3.0.2 packages/cw-schema-derive
+  DRY-RUN   cosmwasm-core v3.0.2 packages/core
 
-  PUBLISH   cw-schema-derive v3.0.2 packages/cw-schema-derive
+  PUBLISH   cosmwasm-core v3.0.2 packages/core
 
-  DRY-RUN   cw-schema v3.0.2 packages/cw-schema
+  DRY-RUN   cosmwasm-crypto v3.0.2 packages/crypto
 
-  PUBLISH   cw-schema v3.0.2 packages/cw-schema
+  PUBLISH   cosmwasm-crypto v3.0.2 packages/crypto
 
-  DRY-RUN   cosmwasm-vm-derive v3.0.2 packages/vm-derive
+  DRY-RUN   cosmwasm-derive v3.0.2 packages/derive
 
-  PUBLISH   cosmwasm-vm-derive v3.0.2 packages/vm-derive
+  PUBLISH   cosmwasm-derive v3.0.2 packages/derive
 
   DRY-RUN   cosmwasm-schema-derive v3.0.2 packages/schema-derive
 
   PUBLISH   cosmwasm-schema-derive v3.0.2 packages/schema-derive
 
-  DRY-RUN   cosmwasm-schema v3.0.2 packages/schema
+  DRY-RUN   cosmwasm-vm-derive v3.0.2 packages/vm-derive
 
-  PUBLISH   cosmwasm-schema v3.0.2 packages/schema
+  PUBLISH   cosmwasm-vm-derive v3.0.2 packages/vm-derive
 
-  DRY-RUN   cosmwasm-derive v3.0.2 packages/derive
+  DRY-RUN   cw-schema-derive v3.0.2 packages/cw-schema-derive
 
-  PUBLISH   cosmwasm-derive v3.0.2 packages/derive
+  PUBLISH   cw-schema-derive v3.0.2 packages/cw-schema-derive
 
-  DRY-RUN   cosmwasm-core v3.0.2 packages/core
+  DRY-RUN   cw-schema v3.0.2 packages/cw-schema
 
-  PUBLISH   cosmwasm-core v3.0.2 packages/core
+  PUBLISH   cw-schema v3.0.2 packages/cw-schema
 
-  DRY-RUN   cosmwasm-crypto v3.0.2 packages/crypto
+  DRY-RUN   cosmwasm-schema v3.0.2 packages/schema
 
-  PUBLISH   cosmwasm-crypto v3.0.2 packages/crypto
+  PUBLISH   cosmwasm-schema v3.0.2 packages/schema
 
   DRY-RUN   cosmwasm-std v3.0.2 packages/std
 
   PUBLISH   cosmwasm-std v3.0.2 packages/std
 
+  REPLACE   cosmwasm-vm v3.0.2 packages/vm
+../../CHANGELOG.md  ## \[Unreleased\]  1 match
+- ## [Unreleased]
++ ## [3.0.2] - {{date}}
+../../CHANGELOG.md  (U|u)nreleased  1 match
+- Unreleased
++ 3.0.2
+../../CHANGELOG.md  <!-- next-header -->  1 match
+- <!-- next-header -->
++ <!-- next-header -->
++
++ ## [Unreleased]
+../../CHANGELOG.md  \.\.\.HEAD  1 match
+- ...HEAD
++ ...v3.0.2
+../../CHANGELOG.md  <!-- next-url -->\n  1 match
+- <!-- next-url -->
++ <!-- next-url -->
++
++ [unreleased]: https://github.com/CosmWasm/cosmwasm/compare/v3.0.2...HEAD
+
   DRY-RUN   cosmwasm-vm v3.0.2 packages/vm
 
   PUBLISH   cosmwasm-vm v3.0.2 packages/vm
@@ -92,6 +114,9 @@ cosmwasm-check  v3.0.2  packages/check
 "#;
 
 /// This test verifies replacing paths with versions.
+///
+/// Pre-release replacements defined for `cosmwasm-vm` are only previewed,
+/// the `CHANGELOG.md` file must stay untouched in dry-run mode.
 #[test]
 fn _0001() {
   // Make a copy of the original Cargo.toml file.
@@ -99,10 +124,17 @@ fn _0001() {
   let original = working_dir.join(Path::new("Cargo.toml"));
   let backup = working_dir.join(Path::new("Cargo.toml.bak"));
   std::fs::copy(&original, &backup).unwrap();
+  let changelog = std::fs::read_to_string(working_dir.join(Path::new("CHANGELOG.md"))).unwrap();
   // Publish workspace crates.
-  cli_assert::command!().code(0).arg("publish").arg("--dry-run").stdout(EXPECTED_STDOUT).stderr("").execute();
+  let mut command = cli_assert::command!().code(0).arg("publish").arg("--dry-run").stderr("");
+  command.execute();
+  // The date of the release is the current one.
+  let date = Regex::new(r"\] - \d{4}-\d{2}-\d{2}").unwrap();
+  assert_eq!(EXPECTED_STDOUT, date.replace_all(&command.get_stdout(), "] - {{date}}"));
   // Make sure the Cargo.toml file is modified properly.
   assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Make sure the CHANGELOG.md file is not modified.
+  assert_eq!(changelog, std::fs::read_to_string(working_dir.join(Path::new("CHANGELOG.md"))).unwrap());
   // Revert changes to Cargo.toml file.
   std::fs::copy(&backup, original).unwrap();
   std::fs::remove_file(backup).unwrap()
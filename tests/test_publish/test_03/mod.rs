@@ -0,0 +1,76 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0" }
+beta = { version = "0.1.0" }
+delta = { version = "0.1.0" }
+epsilon = { version = "0.1.0" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.1.0
+
+Publish crates:
+alpha  v0.1.0  packages/alpha  (level 0)
+beta  v0.1.0  packages/beta  (level 1)
+epsilon  v0.1.0  packages/epsilon  (level 0)
+delta  v0.1.0  packages/delta  (level 2)
+
+
+Level 0: alpha, epsilon
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  DRY-RUN   epsilon v0.1.0 packages/epsilon
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+
+  PUBLISH   epsilon v0.1.0 packages/epsilon
+
+Level 1: beta
+
+  DRY-RUN   beta v0.1.0 packages/beta
+
+  PUBLISH   beta v0.1.0 packages/beta
+
+Level 2: delta
+
+  DRY-RUN   delta v0.1.0 packages/delta
+
+  PUBLISH   delta v0.1.0 packages/delta
+"#;
+
+/// This test verifies publishing crates grouped by topological level.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates concurrently.
+  cli_assert::command!()
+    .code(0)
+    .arg("publish")
+    .arg("--dry-run")
+    .arg("--jobs")
+    .arg("2")
+    .stdout(EXPECTED_STDOUT)
+    .stderr("")
+    .execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
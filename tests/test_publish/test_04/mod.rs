@@ -0,0 +1,56 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+zeta = { version = "0.1.0" }
+alpha = { version = "0.1.0" }
+mu = { version = "0.1.0" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.1.0
+
+Publish crates:
+zeta  v0.1.0  packages/zeta  (level 0)
+alpha  v0.1.0  packages/alpha  (level 0)
+mu  v0.1.0  packages/mu  (level 1)
+
+
+  DRY-RUN   zeta v0.1.0 packages/zeta
+
+  PUBLISH   zeta v0.1.0 packages/zeta
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+
+  DRY-RUN   mu v0.1.0 packages/mu
+
+  PUBLISH   mu v0.1.0 packages/mu
+"#;
+
+/// This test verifies that ties in publishing order are broken using the manifest order by default.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  cli_assert::command!().code(0).arg("publish").arg("--dry-run").stdout(EXPECTED_STDOUT).stderr("").execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+helper = { version = "1.4.0" }
+alpha = { version = "0.1.0" }
+
+[workspace.metadata.univer]
+independent-versions = true
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish crates:
+helper  v1.4.0  packages/helper  (level 0)
+alpha  v0.1.0  packages/alpha  (level 1)
+
+
+  DRY-RUN   helper v1.4.0 packages/helper
+
+  PUBLISH   helper v1.4.0 packages/helper
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+"#;
+
+/// This test verifies publishing crates with own versions next to crates sharing the workspace version.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  cli_assert::command!().code(0).arg("publish").arg("--dry-run").stdout(EXPECTED_STDOUT).stderr("").execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
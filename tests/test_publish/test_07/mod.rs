@@ -0,0 +1,101 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "^0.1.0" }
+beta = { version = "=0.1.0" }
+gamma = { version = "^0.1.0" }
+
+[workspace.metadata.univer.req-style]
+"*" = "^"
+beta = "="
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Configuration:
+  fixed-versions        false
+  independent-versions  false
+  hybrid                false
+  req-style             *=^, beta==
+  exclude               none
+  registry              none
+  tag-template          v{{version}}
+  jobs                  1
+  retries               3
+  retry-delay           5s
+  timeout               none
+  sort                  manifest
+  verify                false
+  max-file-size         1048576
+  forbidden             none
+  integration           none
+  semver-check          false
+  update-lock           true
+  locked                false
+  pre-publish           none
+  post-publish          none
+  pre-develop           none
+  post-develop          none
+
+Publish version: 0.1.0
+
+Publish crates:
+alpha  v0.1.0  packages/alpha  (level 0)
+beta  v0.1.0  packages/beta  (level 0)
+gamma  v0.1.0  packages/gamma  (level 0)
+
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+
+  DRY-RUN   beta v0.1.0 packages/beta
+
+  PUBLISH   beta v0.1.0 packages/beta
+
+  DRY-RUN   gamma v0.1.0 packages/gamma
+
+  PUBLISH   gamma v0.1.0 packages/gamma
+"#;
+
+const EXPECTED_STDERR: &str = r#"debug: loading metadata of workspace ./Cargo.toml
+debug: member alpha v0.1.0 at packages/alpha
+debug: member beta v0.1.0 at packages/beta
+debug: member gamma v0.1.0 at packages/gamma
+debug: writing file {{dir}}/target/univer/backups/{{timestamp}}-publish.toml
+debug: manifest edit: alpha = { path = "packages/alpha" } -> alpha = { version = "^0.1.0" }
+debug: writing file {{dir}}/Cargo.toml
+debug: manifest edit: beta = { path = "packages/beta" } -> beta = { version = "=0.1.0" }
+debug: writing file {{dir}}/Cargo.toml
+debug: manifest edit: gamma = { path = "packages/gamma" } -> gamma = { version = "^0.1.0" }
+debug: writing file {{dir}}/Cargo.toml
+"#;
+
+/// This test verifies publishing with requirement operators configured per crate.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  let mut command = cli_assert::command!().code(0).arg("publish").arg("--dry-run").arg("--verbose").stdout(EXPECTED_STDOUT);
+  command.execute();
+  // Make sure debug traces are reported to the standard error.
+  assert_eq!(EXPECTED_STDERR, normalize_debug(&command.get_stderr()));
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0", features = ["extra"], default-features = false }
+beta = { features = ["extra"], version = "0.1.0", default-features = false }
+gamma = { default-features = false, features = ["extra"], version = "0.1.0" }
+delta = { package = "delta", registry = "local", version = "0.1.0" }
+epsilon = { registry = "local", version = "0.1.0", package = "epsilon", default-features = false }
+"#;
+
+/// This test verifies that other keys of dependencies are preserved in order when publishing.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  cli_assert::command!().code(0).arg("publish").arg("--dry-run").stderr("").execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
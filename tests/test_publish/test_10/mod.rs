@@ -0,0 +1,62 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.5"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha", version = "0.1.5" }
+beta = { path = "packages/beta", version = "0.1.5" }
+gamma = { version = "=0.1.5", path = "packages/gamma", default-features = false }
+
+[workspace.metadata.univer]
+hybrid = true
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.1.5
+
+Publish crates:
+alpha  v0.1.5  packages/alpha  (level 0)
+beta  v0.1.5  packages/beta  (level 1)
+gamma  v0.1.5  packages/gamma  (level 0)
+
+Versions of dependencies differ from versions of crates:
+alpha  0.1.0 -> 0.1.5
+
+
+  DRY-RUN   alpha v0.1.5 packages/alpha
+
+  PUBLISH   alpha v0.1.5 packages/alpha
+
+  DRY-RUN   beta v0.1.5 packages/beta
+
+  PUBLISH   beta v0.1.5 packages/beta
+
+  DRY-RUN   gamma v0.1.5 packages/gamma
+
+  PUBLISH   gamma v0.1.5 packages/gamma
+"#;
+
+/// This test verifies publishing in hybrid mode, versions of dependencies are synchronized and paths are kept.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  cli_assert::command!().code(0).arg("publish").arg("--dry-run").stdout(EXPECTED_STDOUT).stderr("").execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
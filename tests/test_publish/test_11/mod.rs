@@ -0,0 +1,60 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+beta = { path = "packages/beta" }
+gamma = { path = "packages/gamma" }
+
+[workspace.metadata.univer]
+verify = true
+max-file-size = 150
+forbidden = ["*.wasm", "**/*.key"]
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.1.0
+
+Publish crates:
+alpha  v0.1.0  packages/alpha  (level 0)  {{files}} files, {{size}}
+  file 'data/big.txt' is too large (300 B, limit 150 B)
+beta  v0.1.0  packages/beta  (level 0)
+  readme file 'README.md' not found
+gamma  v0.1.0  packages/gamma  (level 0)  {{files}} files, {{size}}
+  file 'blob.wasm' matches forbidden pattern '*.wasm'
+  file 'keys/private.key' matches forbidden pattern '**/*.key'
+
+"#;
+
+/// This test verifies that problems found in packaged crates abort publishing.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Try to publish workspace crates.
+  let mut command = cli_assert::command!()
+    .code(1)
+    .arg("publish")
+    .arg("--dry-run")
+    .stderr("error: verification failed for crates: alpha, beta, gamma\n");
+  command.execute();
+  // Numbers of packaged files and sizes depend on the cargo version, reported problems do not.
+  assert_eq!(EXPECTED_STDOUT, normalize_package(&command.get_stdout()));
+  // Make sure the Cargo.toml file is not modified.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,53 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0" }
+beta = { version = "0.1.0" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.1.0
+
+Publish crates:
+alpha  v0.1.0  packages/alpha  (level 0)  {{files}} files, {{size}}
+beta  v0.1.0  packages/beta  (level 1)  {{files}} files, {{size}}
+
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+
+  DRY-RUN   beta v0.1.0 packages/beta
+
+  PUBLISH   beta v0.1.0 packages/beta
+"#;
+
+/// This test verifies that the number of files and size of packaged crates are presented in the plan.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  let mut command = cli_assert::command!().code(0).arg("publish").arg("--dry-run").arg("--verify").stderr("");
+  command.execute();
+  // Numbers of packaged files and sizes depend on the cargo version, reported problems do not.
+  assert_eq!(EXPECTED_STDOUT, normalize_package(&command.get_stdout()));
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0" }
+beta = { version = "0.1.0" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.1.0
+
+Publish crates:
+alpha  v0.1.0  packages/alpha  (level 0)
+beta  v0.1.0  packages/beta  (level 1)
+
+Integration:
+alpha  v0.1.0  ok
+beta  v0.1.0  ok
+
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+
+  DRY-RUN   beta v0.1.0 packages/beta
+
+  PUBLISH   beta v0.1.0 packages/beta
+"#;
+
+/// This test verifies that packaged crates are built together before publishing.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  cli_assert::command!()
+    .code(0)
+    .arg("publish")
+    .arg("--dry-run")
+    .arg("--integration")
+    .arg("build")
+    .stdout(EXPECTED_STDOUT)
+    .execute();
+  // Make sure the manifest was backed up before it was changed for packaging.
+  let backups_dir = working_dir.join("target").join("univer").join("backups");
+  let mut backups = std::fs::read_dir(backups_dir)
+    .unwrap()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.to_string_lossy().ends_with("-integration.toml"))
+    .collect::<Vec<_>>();
+  backups.sort();
+  assert_eq!(std::fs::read_to_string(&backup).unwrap(), std::fs::read_to_string(backups.last().unwrap()).unwrap());
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
@@ -0,0 +1,8 @@
+pub fn beta() -> u32 {
+  alpha::alpha() + 1
+}
+
+#[test]
+fn test_beta() {
+  assert_eq!(2, beta());
+}
@@ -0,0 +1,5 @@
+mod helper;
+
+pub fn alpha() -> u32 {
+  helper::one()
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+use crate::registry::{REGISTRY_NAME, Registry};
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0", registry = "local" }
+beta = { version = "0.1.0", registry = "local" }
+"#;
+
+/// This test verifies that crates are really published to the registry, in the order of dependencies.
+#[test]
+fn _0001() {
+  // Make a copy of the workspace, published crates are not allowed to have uncommitted changes.
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  // Publish workspace crates to the local registry.
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  // Make sure the crates are published in the order of dependencies.
+  assert_eq!(vec!["alpha 0.1.0", "beta 0.1.0"], registry.published());
+  // Make sure the published dependency refers to the published version.
+  let beta = registry.index("beta");
+  assert_eq!(1, beta.len());
+  assert_eq!("0.1.0", beta[0]["vers"]);
+  assert_eq!("alpha", beta[0]["deps"][0]["name"]);
+  assert_eq!("^0.1.0", beta[0]["deps"][0]["req"]);
+  assert_eq!(0, registry.index("alpha")[0]["deps"].as_array().unwrap().len());
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap());
+}
@@ -0,0 +1,5 @@
+mod value;
+
+pub fn alpha() -> u32 {
+  value::value()
+}
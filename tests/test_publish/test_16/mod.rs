@@ -0,0 +1,62 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.2.0-rc.1"
+
+[workspace.dependencies]
+alpha = { version = "^0.2.0-rc.1" }
+beta = { version = "=0.2.0-rc.1" }
+
+[workspace.metadata.univer]
+req-style = { alpha = "^" }
+"#;
+
+const EXPECTED_STDERR: &str = r#"warning: dependents of 'alpha' may resolve newer pre-releases through '^0.2.0-rc.1', consider '=' requirement
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish version: 0.2.0-rc.1
+
+Publish crates:
+alpha  v0.2.0-rc.1  packages/alpha  (level 0)
+beta  v0.2.0-rc.1  packages/beta  (level 1)
+
+
+  DRY-RUN   alpha v0.2.0-rc.1 packages/alpha
+
+  PUBLISH   alpha v0.2.0-rc.1 packages/alpha
+
+  DRY-RUN   beta v0.2.0-rc.1 packages/beta
+
+  PUBLISH   beta v0.2.0-rc.1 packages/beta
+"#;
+
+/// This test verifies that pre-releases are required exactly by default and other operators are reported.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  std::fs::copy(&original, &backup).unwrap();
+  // Publish workspace crates.
+  cli_assert::command!()
+    .code(0)
+    .arg("publish")
+    .arg("--dry-run")
+    .stdout(EXPECTED_STDOUT)
+    .stderr(EXPECTED_STDERR)
+    .execute();
+  // Make sure the Cargo.toml file is modified properly.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(&original).unwrap());
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
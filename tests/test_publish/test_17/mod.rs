@@ -0,0 +1,58 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0" }
+beta = { version = "0.1.0" }
+gamma = { version = "0.0.5" }
+"#;
+
+const EXPECTED_STDOUT: &str = r#"
+Publish crates:
+alpha  v0.1.0  packages/alpha  (level 0)
+beta  v0.1.0  packages/beta  (level 1)
+
+Unchanged crates (not published):
+gamma  v0.0.5  packages/gamma
+
+
+  DRY-RUN   alpha v0.1.0 packages/alpha
+
+  PUBLISH   alpha v0.1.0 packages/alpha
+
+  DRY-RUN   beta v0.1.0 packages/beta
+
+  PUBLISH   beta v0.1.0 packages/beta
+"#;
+
+/// This test verifies that only crates changed since the previous release are published.
+#[test]
+fn _0001() {
+  let dir = git_workspace(Path::new(file!()).parent().unwrap(), "publish-changed");
+  git(&dir, &["tag", "v0.1.0"]);
+  std::fs::write(dir.join("packages/alpha/src/lib.rs"), "pub fn alpha() -> u32 {\n  2\n}\n").unwrap();
+  // Publish workspace crates.
+  cli_assert::command!()
+    .code(0)
+    .arg("publish")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .arg("--dry-run")
+    .arg("--changed")
+    .arg("--independent-versions")
+    .stdout(EXPECTED_STDOUT)
+    .stderr("")
+    .execute();
+  // Make sure the Cargo.toml file is modified properly, unchanged crates refer to released versions.
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(dir.join("Cargo.toml")).unwrap());
+  std::fs::remove_dir_all(dir).unwrap();
+}
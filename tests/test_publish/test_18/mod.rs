@@ -0,0 +1,77 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_STDOUT_FAILED: &str = r#"
+Publish version: 0.1.1
+
+Publish crates:
+alpha  v0.1.1  packages/alpha  (level 0)
+beta  v0.1.1  packages/beta  (level 1)
+
+Semver check:
+alpha  v0.1.0 -> v0.1.1  failed, major bump required
+  - fn alpha::one
+  + fn alpha::two
+beta  v0.1.0 -> v0.1.1  ok
+"#;
+
+const EXPECTED_STDOUT_PASSED: &str = r#"
+Publish version: 0.2.0
+
+Publish crates:
+alpha  v0.2.0  packages/alpha  (level 0)
+beta  v0.2.0  packages/beta  (level 1)
+
+Semver check:
+alpha  v0.1.0 -> v0.2.0  ok
+  - fn alpha::one
+  + fn alpha::two
+beta  v0.1.0 -> v0.2.0  ok
+
+
+  DRY-RUN   alpha v0.2.0 packages/alpha
+
+  PUBLISH   alpha v0.2.0 packages/alpha
+
+  DRY-RUN   beta v0.2.0 packages/beta
+
+  PUBLISH   beta v0.2.0 packages/beta
+"#;
+
+/// This test verifies that the planned version bump is checked against changes in the public API.
+///
+/// Rustdoc JSON is generated using the nightly toolchain, run with `cargo test -- --ignored`.
+#[test]
+#[ignore = "requires nightly toolchain"]
+fn _0001() {
+  let dir = git_workspace(Path::new(file!()).parent().unwrap(), "semver-check");
+  git(&dir, &["tag", "v0.1.0"]);
+  // Remove one function and add another one, this is a breaking change.
+  std::fs::write(dir.join("packages/alpha/src/lib.rs"), "pub fn alpha() -> u32 {\n  1\n}\n\npub fn two() -> u32 {\n  2\n}\n").unwrap();
+  let manifest_path = dir.join("Cargo.toml");
+  let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+  // The patch bump is too small.
+  std::fs::write(&manifest_path, manifest.replace("version = \"0.1.0\"", "version = \"0.1.1\"")).unwrap();
+  cli_assert::command!()
+    .code(1)
+    .arg("publish")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .arg("--dry-run")
+    .arg("--semver-check")
+    .stdout(EXPECTED_STDOUT_FAILED)
+    .execute();
+  // The major bump (leading zero is not significant) is large enough.
+  std::fs::write(&manifest_path, manifest.replace("version = \"0.1.0\"", "version = \"0.2.0\"")).unwrap();
+  cli_assert::command!()
+    .code(0)
+    .arg("publish")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .arg("--dry-run")
+    .arg("--semver-check")
+    .stdout(EXPECTED_STDOUT_PASSED)
+    .execute();
+  std::fs::remove_dir_all(dir).unwrap();
+}
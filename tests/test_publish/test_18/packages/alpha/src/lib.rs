@@ -0,0 +1,7 @@
+pub fn alpha() -> u32 {
+  1
+}
+
+pub fn one() -> u32 {
+  1
+}
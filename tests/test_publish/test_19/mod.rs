@@ -0,0 +1,46 @@
+use super::*;
+
+use std::path::Path;
+use std::process::Command;
+
+/// This test verifies that the output is silenced, expanded with debug traces or colored on request.
+#[test]
+fn _0001() {
+  let dir = git_workspace(Path::new(file!()).parent().unwrap(), "output");
+  let manifest_path = dir.join("Cargo.toml");
+  let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+  // Quiet output reports only warnings and errors.
+  cli_assert::command!()
+    .code(0)
+    .arg("publish")
+    .arg("--dir")
+    .arg(dir.to_str().unwrap())
+    .arg("--dry-run")
+    .arg("--quiet")
+    .stdout("")
+    .stderr("")
+    .execute();
+  std::fs::write(&manifest_path, &manifest).unwrap();
+  // Verbose output reports debug traces, like edits of the manifest.
+  let output = Command::new(env!("CARGO_BIN_EXE_univer"))
+    .args(["publish", "--dir", dir.to_str().unwrap(), "--dry-run", "--verbose", "--color", "never"])
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stdout.contains("\nConfiguration:\n"));
+  assert!(!stdout.contains('\x1b'));
+  assert!(stderr.contains("debug: loading metadata of workspace "));
+  assert!(stderr.contains("debug: manifest edit: alpha = { path = \"packages/alpha\" } -> alpha = { version = \"0.1.0\" }\n"));
+  assert!(stderr.contains("debug: manifest edit: beta = { path = \"packages/beta\" } -> beta = { version = \"0.1.0\" }\n"));
+  std::fs::write(&manifest_path, &manifest).unwrap();
+  // Colors are forced, even when the output is not a terminal.
+  let output = Command::new(env!("CARGO_BIN_EXE_univer"))
+    .args(["publish", "--dir", dir.to_str().unwrap(), "--dry-run", "--color", "always"])
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert!(String::from_utf8_lossy(&output.stdout).contains("\x1b["));
+  std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Publishes crates in dry-run mode, with specified color environment variables and arguments.
+fn publish(dir: &Path, envs: &[(&str, &str)], args: &[&str]) -> Output {
+  let manifest_path = dir.join("Cargo.toml");
+  let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+  let output = Command::new(env!("CARGO_BIN_EXE_univer"))
+    .args(["publish", "--dir", dir.to_str().unwrap(), "--dry-run", "--verbose", "--integration", "build"])
+    .args(args)
+    .env_remove("NO_COLOR")
+    .env_remove("CARGO_TERM_COLOR")
+    .envs(envs.iter().copied())
+    .output()
+    .unwrap();
+  std::fs::write(&manifest_path, manifest).unwrap();
+  assert!(output.status.success());
+  output
+}
+
+/// Returns `true` when the standard output is colored.
+fn is_colored(output: &Output) -> bool {
+  String::from_utf8_lossy(&output.stdout).contains('\x1b')
+}
+
+/// Returns `true` when cargo was executed with the specified color choice.
+fn cargo_color(output: &Output, color: &str) -> bool {
+  String::from_utf8_lossy(&output.stderr).contains(&format!(" --color={} ", color))
+}
+
+/// This test verifies that the color choice is resolved from the command line and the environment.
+#[test]
+fn _0001() {
+  let dir = git_workspace(Path::new(file!()).parent().unwrap(), "color");
+  // Output redirected to a file is not colored.
+  let output = publish(&dir, &[], &[]);
+  assert!(!is_colored(&output));
+  assert!(cargo_color(&output, "never"));
+  // CARGO_TERM_COLOR is respected.
+  let output = publish(&dir, &[("CARGO_TERM_COLOR", "always")], &[]);
+  assert!(is_colored(&output));
+  assert!(cargo_color(&output, "always"));
+  // NO_COLOR takes precedence over CARGO_TERM_COLOR.
+  let output = publish(&dir, &[("NO_COLOR", "1"), ("CARGO_TERM_COLOR", "always")], &[]);
+  assert!(!is_colored(&output));
+  assert!(cargo_color(&output, "never"));
+  // The command line takes precedence over the environment.
+  let output = publish(&dir, &[("CARGO_TERM_COLOR", "always")], &["--color", "never"]);
+  assert!(!is_colored(&output));
+  assert!(cargo_color(&output, "never"));
+  let output = publish(&dir, &[("NO_COLOR", "1")], &["--color", "always"]);
+  assert!(is_colored(&output));
+  assert!(cargo_color(&output, "always"));
+  std::fs::remove_dir_all(dir).unwrap();
+}
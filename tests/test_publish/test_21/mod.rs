@@ -0,0 +1,46 @@
+use crate::registry::{REGISTRY_NAME, Registry};
+use regex::Regex;
+use std::path::Path;
+
+/// This test verifies that the output of cargo is logged per crate and summarized, also when publishing fails.
+#[test]
+fn _0001() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  // Publish workspace crates to the local registry, the second crate does not compile.
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(!output.status.success());
+  assert_eq!(vec!["alpha 0.1.0"], registry.published());
+  // Make sure the summary reports outcomes of all crates with durations, failures to the standard error.
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let summary = Regex::new(r"\nSummary:\ncrate  version  dry-run +publish\nalpha  v0\.1\.0   ok \d+\.\ds +ok \d+\.\ds\n").unwrap();
+  assert!(summary.is_match(&stdout), "{}", stdout);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let failures = Regex::new(r"\nFailed:\ncrate  version  dry-run +publish\nbeta   v0\.1\.0   failed \d+\.\ds  -\n\nbeta failed, last lines of .*beta\.log:\n").unwrap();
+  assert!(failures.is_match(&stderr), "{}", stderr);
+  assert!(stderr.contains("beta is broken"));
+  assert!(!stdout.contains("beta is broken"));
+  // Make sure the output of cargo is logged per crate.
+  let logs_dir = working_dir.join("target").join("univer").join("logs");
+  let alpha_log = std::fs::read_to_string(logs_dir.join("alpha.log")).unwrap();
+  assert!(alpha_log.contains("$ cargo publish --dry-run --registry local --color=never\n"));
+  assert!(alpha_log.contains("$ cargo publish --registry local --color=never\n"));
+  let beta_log = std::fs::read_to_string(logs_dir.join("beta.log")).unwrap();
+  assert!(beta_log.contains("$ cargo publish --dry-run --registry local --color=never\n"));
+  assert!(beta_log.contains("error: beta is broken"));
+  assert!(!beta_log.contains("$ cargo publish --registry"));
+}
+
+/// This test verifies that failures are reported also when the output is quiet.
+#[test]
+fn _0002() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--quiet"]);
+  assert!(!output.status.success());
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  assert!(!stdout.contains("Summary:"), "{}", stdout);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stderr.contains("\nFailed:\n"), "{}", stderr);
+  assert!(stderr.contains("beta is broken"), "{}", stderr);
+}
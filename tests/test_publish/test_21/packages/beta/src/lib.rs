@@ -0,0 +1,5 @@
+compile_error!("beta is broken");
+
+pub fn beta() -> u32 {
+  alpha::alpha() + 1
+}
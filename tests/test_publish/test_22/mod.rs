@@ -0,0 +1,68 @@
+use crate::registry::{REGISTRY_NAME, Registry};
+use std::path::Path;
+
+/// Returns the number of `cargo publish` commands (without dry run) logged for the crate.
+fn attempts(working_dir: &Path, name: &str) -> usize {
+  let log = std::fs::read_to_string(working_dir.join("target").join("univer").join("logs").join(format!("{}.log", name))).unwrap();
+  log.matches("$ cargo publish --registry").count()
+}
+
+/// This test verifies that publishing is retried after transient registry errors.
+#[test]
+fn _0001() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  registry.fail_publish(&["429 Too Many Requests", "503 Service Unavailable"]);
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(output.status.success(), "{}", stderr);
+  assert_eq!(vec!["alpha 0.1.0"], registry.published());
+  assert!(stderr.contains("warning: publishing 'alpha' failed with a transient error, retry 1 of 3 in 0s"));
+  assert!(stderr.contains("warning: publishing 'alpha' failed with a transient error, retry 2 of 3 in 0s"));
+  assert_eq!(3, attempts(&working_dir, "alpha"));
+}
+
+/// This test verifies that publishing is not retried after permanent registry errors, even reported with server error status.
+#[test]
+fn _0002() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  registry.fail_publish(&["503 crate version `alpha@0.1.0` already exists"]);
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(!output.status.success());
+  assert!(registry.published().is_empty());
+  assert!(!stderr.contains("retry"));
+  assert_eq!(1, attempts(&working_dir, "alpha"));
+}
+
+/// This test verifies that hung publishing is killed after the timeout and retried.
+#[test]
+fn _0003() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  registry.fail_publish(&["hang"]);
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--timeout", "5"]);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(output.status.success(), "{}", stderr);
+  assert_eq!(vec!["alpha 0.1.0"], registry.published());
+  assert!(stderr.contains("warning: publishing 'alpha' failed with a transient error, retry 1 of 3 in 0s"));
+  let log = std::fs::read_to_string(working_dir.join("target").join("univer").join("logs").join("alpha.log")).unwrap();
+  assert!(log.contains("command timed out after 5s and was killed"));
+  assert_eq!(2, attempts(&working_dir, "alpha"));
+}
+
+/// This test verifies that the crate published by the hung attempt is not reported as failed by the retry.
+#[test]
+fn _0004() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  registry.fail_publish(&["hang after publish"]);
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--timeout", "5"]);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(output.status.success(), "{}", stderr);
+  assert_eq!(vec!["alpha 0.1.0"], registry.published());
+  assert!(stderr.contains("warning: publishing 'alpha' failed with a transient error, retry 1 of 3 in 0s"));
+  assert!(stderr.contains("warning: 'alpha' is already published, the previous attempt succeeded"));
+  assert_eq!(2, attempts(&working_dir, "alpha"));
+}
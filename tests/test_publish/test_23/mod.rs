@@ -0,0 +1,17 @@
+use std::path::Path;
+
+/// This test verifies that invalid pre-release replacements are reported before anything is published.
+#[test]
+fn _0001() {
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap();
+  cli_assert::command!()
+    .code(1)
+    .arg("publish")
+    .arg("--accept-all")
+    .stdout("")
+    .stderr("error: expected exactly 1 match(es) for 'Unreleased' in file 'README.md' but found 0, crate 'beta'\n")
+    .execute();
+  // Make sure the workspace manifest is not modified.
+  assert_eq!(original, std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap());
+}
@@ -0,0 +1,46 @@
+use crate::registry::{REGISTRY_NAME, Registry};
+use regex::Regex;
+use std::path::Path;
+
+const EXPECTED_PUBLISHED: &str = r#"workspace pre-publish 0.1.0 alpha beta false
+alpha pre-publish 0.1.0 packages/alpha
+alpha post-publish 0.1.0 packages/alpha
+beta pre-publish 0.1.0 packages/beta
+beta post-publish 0.1.0 packages/beta
+workspace post-publish 0.1.0 alpha beta false
+"#;
+
+const EXPECTED_DECLINED: &str = r#"workspace pre-publish 0.1.0 alpha beta false
+alpha pre-publish 0.1.0 packages/alpha
+beta pre-publish 0.1.0 packages/beta
+workspace post-publish 0.1.0 alpha beta false
+"#;
+
+/// Returns lines written by hooks, with paths of crates relative to the workspace root.
+fn hooks_log(working_dir: &Path) -> String {
+  let content = std::fs::read_to_string(working_dir.join("hooks.log")).unwrap();
+  Regex::new(r"\S*[/\\]packages[/\\](\w+)").unwrap().replace_all(&content, "packages/$1").to_string()
+}
+
+/// This test verifies that publish hooks are executed with environment variables describing the workspace and crates.
+#[test]
+fn _0001() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec!["alpha 0.1.0", "beta 0.1.0"], registry.published());
+  assert_eq!(EXPECTED_PUBLISHED, hooks_log(&working_dir));
+}
+
+/// This test verifies that crate hooks after publishing are not executed when publishing is declined.
+#[test]
+fn _0002() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  // Accept the version and the list of crates, decline the dry-run and publishing of each crate.
+  let output = registry.univer_with_input(&working_dir, &["publish", "--registry", REGISTRY_NAME], "Y\nY\nN\nN\nN\nN\n");
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert!(registry.published().is_empty());
+  assert_eq!(EXPECTED_DECLINED, hooks_log(&working_dir));
+}
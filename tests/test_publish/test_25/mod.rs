@@ -0,0 +1,44 @@
+use super::*;
+
+use crate::registry::{REGISTRY_NAME, Registry};
+use std::path::Path;
+
+const EXPECTED_FILE: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { version = "0.1.0", registry = "local" }
+beta = { version = "0.1.0", registry = "local" }
+gamma = { version = "0.1.0", registry = "local" }
+"#;
+
+/// This test verifies that crates in the same level are really published concurrently.
+#[test]
+fn _0001() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--jobs", "2"]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  assert!(stdout.contains("\nLevel 0: alpha\n"), "{}", stdout);
+  assert!(stdout.contains("\nLevel 1: beta, gamma\n"), "{}", stdout);
+  // Make sure the dependency is published first, then both dependents.
+  let mut published = registry.published();
+  assert_eq!("alpha 0.1.0", published.remove(0));
+  published.sort();
+  assert_eq!(vec!["beta 0.1.0", "gamma 0.1.0"], published);
+  // Make sure concurrently published crates are built in their own target directories.
+  let univer_dir = working_dir.join("target").join("univer");
+  for name in ["beta", "gamma"] {
+    let log = std::fs::read_to_string(univer_dir.join("logs").join(format!("{}.log", name))).unwrap();
+    assert!(log.contains("--target-dir"), "{}", log);
+    assert!(univer_dir.join("jobs").join(name).join("package").exists());
+  }
+  assert!(!univer_dir.join("jobs").join("alpha").exists());
+  assert_eq!(normalize(EXPECTED_FILE), std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap());
+}
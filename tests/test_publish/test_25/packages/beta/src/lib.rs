@@ -0,0 +1,3 @@
+pub fn beta() -> u32 {
+  alpha::alpha() + 1
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+
+/// This test verifies that publishing in hybrid mode is aborted when synchronizing mismatched versions is declined.
+#[test]
+fn _0001() {
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap();
+  // Accept the version, decline synchronizing versions.
+  cli_assert::command!()
+    .code(1)
+    .arg("publish")
+    .stdin("Y\nN\n")
+    .stderr("error: versions of dependencies do not match versions of crates: alpha\n")
+    .execute();
+  // Make sure the Cargo.toml file is not modified.
+  assert_eq!(original, std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap());
+}
@@ -0,0 +1,61 @@
+use crate::registry::{REGISTRY_NAME, Registry};
+use regex::Regex;
+use std::path::Path;
+
+const EXPECTED_LOCK_CHANGES: &str = r#"
+Cargo.lock:
++ alpha  v0.1.0  sparse+http://127.0.0.1:{{port}}/index/
+"#;
+
+const STALE_LOCK: &str = r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "alpha"
+version = "0.1.0"
+"#;
+
+/// Generates the lock file of the workspace using the local registry.
+fn generate_lock(registry: &Registry, working_dir: &Path) {
+  let status = std::process::Command::new("cargo")
+    .arg("generate-lockfile")
+    .current_dir(working_dir)
+    .envs(registry.envs())
+    .status()
+    .unwrap();
+  assert!(status.success());
+}
+
+/// This test verifies that changes of the lock file updated after publishing are reported.
+#[test]
+fn _0001() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  generate_lock(&registry, &working_dir);
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--locked"]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  // The published dependency is locked from the registry, next to the workspace crate.
+  let port = Regex::new(r"127\.0\.0\.1:\d+").unwrap();
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let stdout = port.replace_all(&stdout, "127.0.0.1:{{port}}");
+  assert!(stdout.contains(EXPECTED_LOCK_CHANGES), "{}", stdout);
+  let lock = std::fs::read_to_string(working_dir.join("Cargo.lock")).unwrap();
+  assert!(lock.contains("\"alpha 0.1.0 (sparse+http://127.0.0.1:"), "{}", lock);
+}
+
+/// This test verifies that the stale lock file is reported before anything is changed or published.
+#[test]
+fn _0002() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  std::fs::write(working_dir.join("Cargo.lock"), STALE_LOCK).unwrap();
+  let manifest = std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap();
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--locked"]);
+  assert!(!output.status.success());
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stderr.starts_with("error: Cargo.lock is not up to date, reason:"), "{}", stderr);
+  assert_eq!(STALE_LOCK, std::fs::read_to_string(working_dir.join("Cargo.lock")).unwrap());
+  assert_eq!(manifest, std::fs::read_to_string(working_dir.join("Cargo.toml")).unwrap());
+  assert!(registry.published().is_empty());
+}
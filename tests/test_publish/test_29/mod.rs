@@ -0,0 +1,25 @@
+use crate::git;
+use crate::registry::{REGISTRY_NAME, Registry};
+use std::path::Path;
+
+/// This test verifies that crates with files rewritten by pre-release replacements are published from a git repository.
+#[test]
+fn _0001() {
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  git(&working_dir, &["init", "--quiet"]);
+  git(&working_dir, &["add", "--all"]);
+  git(&working_dir, &["commit", "--quiet", "--message", "initial"]);
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec!["alpha 0.1.0", "beta 0.1.0"], registry.published());
+  // Make sure the replaced file was published.
+  let readme = std::fs::read_to_string(working_dir.join("packages/beta/README.md")).unwrap();
+  assert_eq!("# beta\n\nVersion: 0.1.0\n", readme);
+  // Make sure only the crate with rewritten files is published from the dirty working directory.
+  let logs_dir = working_dir.join("target").join("univer").join("logs");
+  let alpha_log = std::fs::read_to_string(logs_dir.join("alpha.log")).unwrap();
+  assert!(!alpha_log.contains("--allow-dirty"), "{}", alpha_log);
+  let beta_log = std::fs::read_to_string(logs_dir.join("beta.log")).unwrap();
+  assert!(beta_log.contains("$ cargo publish --registry local --allow-dirty --color=never\n"), "{}", beta_log);
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+use std::path::Path;
+
+const EXPECTED_DEVELOP: &str = r#"[workspace]
+members = ["packages/*"]
+
+resolver = "2"
+
+[workspace.package]
+version = "0.1.0"
+
+[workspace.dependencies]
+alpha = { path = "packages/alpha" }
+beta = { path = "packages/beta" }
+"#;
+
+/// This test verifies restoring the workspace manifest from the backup created automatically.
+#[test]
+fn _0001() {
+  // Make a copy of the original Cargo.toml file and remove backups left by previous runs.
+  let working_dir = Path::new(file!()).parent().unwrap();
+  let original = working_dir.join(Path::new("Cargo.toml"));
+  let backup = working_dir.join(Path::new("Cargo.toml.bak"));
+  let backups_dir = working_dir.join(Path::new("target/univer/backups"));
+  std::fs::copy(&original, &backup).unwrap();
+  let _ = std::fs::remove_dir_all(&backups_dir);
+  // There are no backups yet.
+  cli_assert::command!().code(1).arg("restore").stdout("").stderr("error: no backups found\n").execute();
+  // Replace version numbers with local paths, the manifest is backed up.
+  cli_assert::command!().code(0).arg("develop").arg("--no-update-lock").stderr("").execute();
+  assert_eq!(normalize(EXPECTED_DEVELOP), std::fs::read_to_string(&original).unwrap());
+  // Restore the newest backup, the manifest is backed up again.
+  cli_assert::command!().code(0).arg("restore").arg("--backup").arg("1").arg("-y").stderr("").execute();
+  assert_eq!(std::fs::read_to_string(&backup).unwrap(), std::fs::read_to_string(&original).unwrap());
+  assert_eq!(2, std::fs::read_dir(&backups_dir).unwrap().count());
+  // Restore the backup made before restoring, which undoes the previous restore.
+  cli_assert::command!().code(0).arg("restore").arg("--backup").arg("1").arg("-y").stderr("").execute();
+  assert_eq!(normalize(EXPECTED_DEVELOP), std::fs::read_to_string(&original).unwrap());
+  // Restoring not existing backup fails.
+  cli_assert::command!()
+    .code(1)
+    .arg("restore")
+    .arg("--backup")
+    .arg("9")
+    .stderr("error: backup 9 not found\n")
+    .execute();
+  // Revert changes to Cargo.toml file.
+  std::fs::copy(&backup, original).unwrap();
+  std::fs::remove_file(backup).unwrap()
+}
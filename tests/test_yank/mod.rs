@@ -0,0 +1,3 @@
+mod test_01;
+mod test_02;
+mod test_03;
@@ -0,0 +1,24 @@
+use crate::registry::{REGISTRY_NAME, Registry};
+use std::path::Path;
+
+/// This test verifies that all crates are yanked in reverse publishing order and the yank can be undone.
+#[test]
+fn _0001() {
+  // Publish workspace crates to the local registry, from the copy of the workspace.
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  let output = registry.univer(&working_dir, &["publish", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  // Yank the published version.
+  let output = registry.univer(&working_dir, &["yank", "--version", "0.1.0", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec!["yank beta 0.1.0", "yank alpha 0.1.0"], registry.yanked());
+  assert_eq!(true, registry.index("alpha")[0]["yanked"]);
+  assert_eq!(true, registry.index("beta")[0]["yanked"]);
+  // Undo the yank.
+  let output = registry.univer(&working_dir, &["yank", "--version", "0.1.0", "--undo", "--accept-all", "--registry", REGISTRY_NAME]);
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec!["yank beta 0.1.0", "yank alpha 0.1.0", "unyank beta 0.1.0", "unyank alpha 0.1.0"], registry.yanked());
+  assert_eq!(false, registry.index("alpha")[0]["yanked"]);
+  assert_eq!(false, registry.index("beta")[0]["yanked"]);
+}
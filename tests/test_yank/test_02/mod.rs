@@ -0,0 +1,39 @@
+const EXPECTED_STDOUT: &str = r#"
+Yank version: 0.1.0
+
+Yank crates:
+beta  v0.1.0  packages/beta
+alpha  v0.1.0  packages/alpha
+
+
+  YANK   beta v0.1.0 packages/beta
+
+  YANK   alpha v0.1.0 packages/alpha
+"#;
+
+/// This test verifies that crates to be yanked are listed in reverse publishing order.
+#[test]
+fn _0001() {
+  cli_assert::command!()
+    .code(0)
+    .arg("yank")
+    .arg("--version")
+    .arg("0.1.0")
+    .arg("--dry-run")
+    .stdout(EXPECTED_STDOUT)
+    .stderr("")
+    .execute();
+}
+
+/// This test verifies that the invalid version is reported before anything is asked.
+#[test]
+fn _0002() {
+  cli_assert::command!()
+    .code(1)
+    .arg("yank")
+    .arg("--version")
+    .arg("1.x")
+    .stdout("")
+    .stderr("error: invalid version '1.x', reason: unexpected character 'x' while parsing minor version number\n")
+    .execute();
+}
@@ -0,0 +1,3 @@
+pub fn alpha() -> u32 {
+  1
+}
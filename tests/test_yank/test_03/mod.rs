@@ -0,0 +1,32 @@
+use crate::registry::{REGISTRY_NAME, Registry};
+use std::path::Path;
+
+const EXPECTED_RESULTS: &str = r#"
+Results:
+beta  v0.1.0  failed
+alpha  v0.1.0  ok
+"#;
+
+/// This test verifies that crates with other versions are skipped and failures do not stop yanking remaining crates.
+#[test]
+fn _0001() {
+  // Publish only one crate to the local registry, yanking the other one with the same version fails.
+  let registry = Registry::start();
+  let working_dir = registry.workspace(Path::new(file!()).parent().unwrap());
+  let output = registry.univer(
+    &working_dir,
+    &["publish", "--accept-all", "--registry", REGISTRY_NAME, "--exclude", "beta", "--exclude", "gamma"],
+  );
+  assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+  assert_eq!(vec!["alpha 0.1.0"], registry.published());
+  // Yank the published version.
+  let output = registry.univer(&working_dir, &["yank", "--version", "0.1.0", "--accept-all", "--registry", REGISTRY_NAME]);
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(!output.status.success());
+  assert!(stdout.contains("\nSkipped crates, versions differ:\ngamma  v0.2.0  packages/gamma\n"), "{}", stdout);
+  assert!(stdout.contains(EXPECTED_RESULTS), "{}", stdout);
+  assert!(stderr.contains("warning: yank of crate 'beta' failed"), "{}", stderr);
+  assert!(stderr.ends_with("error: yank failed for crates: beta\n"), "{}", stderr);
+  assert_eq!(vec!["yank alpha 0.1.0"], registry.yanked());
+}
@@ -0,0 +1,3 @@
+pub fn gamma() -> u32 {
+  3
+}